@@ -1,6 +1,7 @@
 mod borrow_mut;
 mod for_each;
 mod until_change;
+mod watch;
 use std::{
     cell::{BorrowError, BorrowMutError, Ref, RefCell},
     fmt::Debug,
@@ -17,7 +17,7 @@ impl<T> ReactiveCell<T> {
 }
 
 pub struct UntilChangeFuture<'a, T> {
-    target: &'a ReactiveCell<T>,
+    pub(super) target: &'a ReactiveCell<T>,
     last_version: u64,
     waker_slot: WakerSlot,
 }
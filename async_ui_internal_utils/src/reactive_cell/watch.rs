@@ -0,0 +1,83 @@
+use std::task::Poll;
+
+use futures_core::Stream;
+
+use super::{until_change::UntilChangeFuture, ReactiveCell};
+
+impl<T> ReactiveCell<T> {
+    /// Get a Stream that yields `func(&value)` right away,
+    /// then again after every time the cell is mutably borrowed.
+    ///
+    /// Like [until_change][ReactiveCell::until_change], changes made between
+    /// polls are merged: you get the latest value, not every intermediate one.
+    ///
+    /// ```rust
+    /// # use async_ui_internal_utils::reactive_cell::ReactiveCell;
+    /// use futures_lite::StreamExt;
+    /// # let _ = async {
+    ///     let cell = ReactiveCell::new(123);
+    ///     let mut is_even = cell.watch(|v| v % 2 == 0);
+    ///     while let Some(even) = is_even.next().await {
+    ///         println!("even: {even}");
+    ///     }
+    /// # };
+    /// ```
+    pub fn watch<U, F: FnMut(&T) -> U>(&self, func: F) -> WatchStream<'_, T, F> {
+        WatchStream {
+            until_change: self.until_change(),
+            func,
+            started: false,
+        }
+    }
+}
+
+pub struct WatchStream<'a, T, F> {
+    until_change: UntilChangeFuture<'a, T>,
+    func: F,
+    started: bool,
+}
+
+impl<'a, T, U, F: FnMut(&T) -> U + Unpin> Stream for WatchStream<'a, T, F> {
+    type Item = U;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let changed = std::pin::Pin::new(&mut this.until_change).poll_next(cx);
+        // The first poll of until_change only records the current version,
+        // so it never reports a change; we yield the initial value instead.
+        if !this.started || changed.is_ready() {
+            this.started = true;
+            Poll::Ready(Some((this.func)(&this.until_change.target.borrow())))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_lite::{future::block_on, StreamExt};
+
+    use super::*;
+
+    #[test]
+    fn yields_initial_then_changes() {
+        let cell = ReactiveCell::new(1);
+        let mut doubled = cell.watch(|v| v * 2);
+        block_on(async {
+            assert_eq!(doubled.next().await, Some(2));
+            assert!(futures_lite::future::poll_once(doubled.next())
+                .await
+                .is_none());
+            *cell.borrow_mut() = 5;
+            *cell.borrow_mut() = 6;
+            assert_eq!(doubled.next().await, Some(12));
+            assert!(futures_lite::future::poll_once(doubled.next())
+                .await
+                .is_none());
+        });
+    }
+}
@@ -0,0 +1,191 @@
+//! Easing functions for [tween][super::tween].
+//!
+//! An easing function takes the elapsed fraction of an animation's duration
+//! (from 0 to 1) and returns the fraction of the distance that should have been
+//! traveled. Every function here maps 0 to 0 and 1 to 1.
+//!
+//! [ease], [ease_in], [ease_out], and [ease_in_out] match the CSS keywords of
+//! the same names. Use [cubic_bezier] for other CSS-style curves.
+
+/// Constant speed.
+pub fn linear(t: f64) -> f64 {
+    t
+}
+
+/// Like CSS `ease`: speeds up quickly, then slows down gradually.
+pub fn ease(t: f64) -> f64 {
+    bezier(0.25, 0.1, 0.25, 1.0, t)
+}
+
+/// Like CSS `ease-in`: starts slowly.
+pub fn ease_in(t: f64) -> f64 {
+    bezier(0.42, 0.0, 1.0, 1.0, t)
+}
+
+/// Like CSS `ease-out`: ends slowly.
+pub fn ease_out(t: f64) -> f64 {
+    bezier(0.0, 0.0, 0.58, 1.0, t)
+}
+
+/// Like CSS `ease-in-out`: starts and ends slowly.
+pub fn ease_in_out(t: f64) -> f64 {
+    bezier(0.42, 0.0, 0.58, 1.0, t)
+}
+
+/// Quadratic ease in.
+pub fn ease_in_quad(t: f64) -> f64 {
+    t * t
+}
+
+/// Quadratic ease out.
+pub fn ease_out_quad(t: f64) -> f64 {
+    1.0 - (1.0 - t) * (1.0 - t)
+}
+
+/// Cubic ease in.
+pub fn ease_in_cubic(t: f64) -> f64 {
+    t * t * t
+}
+
+/// Cubic ease out.
+pub fn ease_out_cubic(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Cubic ease in and out.
+pub fn ease_in_out_cubic(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// Like CSS `cubic-bezier(x1, y1, x2, y2)`.
+///
+/// ```
+/// # use async_ui_web::animation::{easing::cubic_bezier, tween};
+/// # use std::time::Duration;
+/// # let _ = async {
+/// let overshoot = cubic_bezier(0.34, 1.56, 0.64, 1.0);
+/// let _ = tween(0.0, 1.0, Duration::from_millis(300), overshoot);
+/// # };
+/// ```
+///
+/// **Panics** if `x1` or `x2` is outside of 0..=1
+/// (the curve would not be a function of time).
+pub fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64) -> impl Fn(f64) -> f64 + Clone {
+    assert!(
+        (0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2),
+        "cubic bezier x values must be between 0 and 1"
+    );
+    move |t| bezier(x1, y1, x2, y2, t)
+}
+
+/// Evaluate the cubic bezier from (0, 0) to (1, 1) with the given control points
+/// at the point whose x coordinate is `t`.
+fn bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= 1.0 {
+        return 1.0;
+    }
+    // polynomial coefficients of each coordinate in terms of the curve parameter
+    let cx = 3.0 * x1;
+    let bx = 3.0 * (x2 - x1) - cx;
+    let ax = 1.0 - cx - bx;
+    let cy = 3.0 * y1;
+    let by = 3.0 * (y2 - y1) - cy;
+    let ay = 1.0 - cy - by;
+    let sample_x = |s: f64| ((ax * s + bx) * s + cx) * s;
+    let sample_dx = |s: f64| (3.0 * ax * s + 2.0 * bx) * s + cx;
+    let sample_y = |s: f64| ((ay * s + by) * s + cy) * s;
+
+    // Find the parameter for x = t: Newton's method first, as it's fast,
+    // falling back to bisection (x is monotonic in the parameter) if it fails.
+    let mut s = t;
+    for _ in 0..8 {
+        let err = sample_x(s) - t;
+        if err.abs() < 1e-7 {
+            return sample_y(s);
+        }
+        let d = sample_dx(s);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s -= err / d;
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    s = t;
+    for _ in 0..64 {
+        let x = sample_x(s);
+        if (x - t).abs() < 1e-7 {
+            break;
+        }
+        if x < t {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = (lo + hi) / 2.0;
+    }
+    sample_y(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &[fn(f64) -> f64] = &[
+        linear,
+        ease,
+        ease_in,
+        ease_out,
+        ease_in_out,
+        ease_in_quad,
+        ease_out_quad,
+        ease_in_cubic,
+        ease_out_cubic,
+        ease_in_out_cubic,
+    ];
+
+    #[test]
+    fn endpoints() {
+        for f in ALL {
+            assert!(f(0.0).abs() < 1e-9);
+            assert!((f(1.0) - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn monotonic() {
+        for f in ALL {
+            let values: Vec<f64> = (0..=100).map(|i| f(i as f64 / 100.0)).collect();
+            assert!(values.windows(2).all(|w| w[0] <= w[1] + 1e-9));
+        }
+    }
+
+    #[test]
+    fn linear_bezier() {
+        let f = cubic_bezier(0.25, 0.25, 0.75, 0.75);
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            assert!((f(t) - t).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn ease_in_out_is_symmetric() {
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            assert!((ease_in_out(t) + ease_in_out(1.0 - t) - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn bezier_overshoot() {
+        let f = cubic_bezier(0.34, 1.56, 0.64, 1.0);
+        assert!((0..=100).any(|i| f(i as f64 / 100.0) > 1.0));
+    }
+}
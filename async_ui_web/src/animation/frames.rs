@@ -0,0 +1,118 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use async_ui_internal_utils::dummy_waker::dummy_waker;
+use async_ui_web_core::window::WINDOW;
+use futures_lite::Stream;
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+
+/// A struct implementing both [Future] and [Stream].
+/// Yields the timestamp (in milliseconds, comparable to `performance.now()`)
+/// of each frame.
+///
+/// Use [animation_frames] to get this struct.
+pub struct AnimationFrames {
+    shared: Rc<RefCell<Shared>>,
+    closure: Closure<dyn Fn(f64)>,
+}
+
+struct Shared {
+    /// The timestamp of a frame that has arrived but not yet been yielded.
+    timestamp: Option<f64>,
+    /// The ID of our pending `requestAnimationFrame` call.
+    request: Option<i32>,
+    waker: Waker,
+}
+
+/// Wait for the browser to paint frames.
+///
+/// The returned object is both a [Future] (resolving at the next frame) and a
+/// never-ending [Stream] (yielding at every frame).
+///
+/// ```
+/// # use async_ui_web::animation::animation_frames;
+/// # use futures_lite::StreamExt;
+/// # let _ = async {
+/// let mut frames = animation_frames();
+/// while let Some(timestamp) = frames.next().await {
+///     // update something every frame
+/// }
+/// # };
+/// ```
+///
+/// A frame is only requested while the stream is being polled,
+/// so a stream that isn't awaited doesn't keep the browser busy.
+/// If the code between polls takes longer than a frame, the frames in between
+/// are skipped.
+///
+/// See [MDN documentation for `requestAnimationFrame`](https://developer.mozilla.org/en-US/docs/Web/API/Window/requestAnimationFrame).
+#[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+pub fn animation_frames() -> AnimationFrames {
+    let shared = Rc::new(RefCell::new(Shared {
+        timestamp: None,
+        request: None,
+        waker: dummy_waker(),
+    }));
+    let shared_weak = Rc::downgrade(&shared);
+    let closure = Closure::new(move |timestamp: f64| {
+        if let Some(strong) = shared_weak.upgrade() {
+            let shared = &mut *strong.borrow_mut();
+            shared.request = None;
+            shared.timestamp = Some(timestamp);
+            shared.waker.wake_by_ref();
+        }
+        async_ui_web_core::executor::run_now();
+    });
+    AnimationFrames { shared, closure }
+}
+
+impl Future for AnimationFrames {
+    type Output = f64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(Some(timestamp)) => Poll::Ready(timestamp),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl Stream for AnimationFrames {
+    type Item = f64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let shared = &mut *this.shared.borrow_mut();
+        let waker = cx.waker();
+        if !shared.waker.will_wake(waker) {
+            shared.waker = waker.to_owned();
+        }
+        if let Some(timestamp) = shared.timestamp.take() {
+            return Poll::Ready(Some(timestamp));
+        }
+        if shared.request.is_none() {
+            let request = WINDOW
+                .with(|window| {
+                    window.request_animation_frame(this.closure.as_ref().unchecked_ref())
+                })
+                .unwrap_throw();
+            shared.request = Some(request);
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for AnimationFrames {
+    fn drop(&mut self) {
+        if let Some(request) = self.shared.borrow_mut().request.take() {
+            WINDOW
+                .with(|window| window.cancel_animation_frame(request))
+                .unwrap_throw();
+        }
+    }
+}
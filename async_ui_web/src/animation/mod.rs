@@ -0,0 +1,51 @@
+//! Frame clock and utilities for animating values.
+//!
+//! Animations in Async UI Web are plain async loops: get a Stream of values,
+//! and apply each value to the DOM as it comes.
+//!
+//! ```
+//! # use async_ui_web::{animation::{easing, tween}, html::Div};
+//! # use futures_lite::StreamExt;
+//! # use std::time::Duration;
+//! # let _ = async {
+//! # let div = Div::new();
+//! let mut opacity = tween(0.0, 1.0, Duration::from_millis(300), easing::ease_out);
+//! while let Some(value) = opacity.next().await {
+//!     div.style().set_property("opacity", &value.to_string()).ok();
+//! }
+//! # };
+//! ```
+//!
+//! *   [animation_frames] yields a timestamp for every frame the browser paints.
+//! *   [tween] moves from one value to another over a fixed duration,
+//!     shaped by an [easing] function.
+//! *   [spring] moves toward a target value following spring physics.
+//!
+//! All of these only request frames from the browser while being polled,
+//! and cancel their pending frame request when dropped.
+//! If the user has asked for reduced motion (see [prefers_reduced_motion]),
+//! [tween] and [spring] jump straight to their final value.
+
+pub mod easing;
+mod frames;
+mod spring;
+mod tween;
+
+pub use frames::{animation_frames, AnimationFrames};
+pub use spring::{spring, Spring, SpringAnimation};
+pub use tween::{tween, Tween};
+
+use async_ui_web_core::window::WINDOW;
+
+/// Check whether the user has asked the system to minimize non-essential motion.
+///
+/// See [MDN documentation for `prefers-reduced-motion`](https://developer.mozilla.org/en-US/docs/Web/CSS/@media/prefers-reduced-motion).
+pub fn prefers_reduced_motion() -> bool {
+    WINDOW.with(|window| {
+        window
+            .match_media("(prefers-reduced-motion: reduce)")
+            .ok()
+            .flatten()
+            .is_some_and(|query| query.matches())
+    })
+}
@@ -0,0 +1,265 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_lite::Stream;
+
+use super::{animation_frames, prefers_reduced_motion, AnimationFrames};
+
+/// Physical parameters of a [spring] animation.
+///
+/// The spring pulls the value toward the target with a force proportional to
+/// the distance (`stiffness`), slowed down by friction proportional to the
+/// velocity (`damping`). A heavier `mass` makes the spring slower to react.
+///
+/// The default is a moderately stiff spring with barely any overshoot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub stiffness: f64,
+    pub damping: f64,
+    pub mass: f64,
+    /// The spring is considered at rest when both its distance from the target
+    /// and its velocity (per second) are below this.
+    pub precision: f64,
+}
+
+impl Spring {
+    /// Slow and soft, with a little overshoot.
+    pub const GENTLE: Self = Self {
+        stiffness: 120.0,
+        damping: 14.0,
+        mass: 1.0,
+        precision: 0.01,
+    };
+    /// Bouncy, with visible overshoot.
+    pub const WOBBLY: Self = Self {
+        stiffness: 180.0,
+        damping: 12.0,
+        mass: 1.0,
+        precision: 0.01,
+    };
+    /// Fast and snappy.
+    pub const STIFF: Self = Self {
+        stiffness: 210.0,
+        damping: 20.0,
+        mass: 1.0,
+        precision: 0.01,
+    };
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self {
+            stiffness: 170.0,
+            damping: 26.0,
+            mass: 1.0,
+            precision: 0.01,
+        }
+    }
+}
+
+/// A [Stream] yielding one value per frame as it springs toward a target.
+///
+/// Use [spring] to get this struct.
+pub struct SpringAnimation {
+    frames: AnimationFrames,
+    state: SpringState,
+    last_frame: Option<f64>,
+    done: bool,
+}
+
+/// Move from `from` toward `to` following spring physics,
+/// yielding the value at every frame.
+///
+/// The Stream ends after yielding `to` once the spring comes to rest.
+/// If [reduced motion][super::prefers_reduced_motion] is preferred,
+/// the Stream yields only `to`.
+///
+/// Unlike [tween][super::tween], the target can be changed while the animation
+/// runs (see [set_target][SpringAnimation::set_target]), and the value will
+/// smoothly change course, keeping its current velocity.
+///
+/// ```
+/// # use async_ui_web::{animation::{spring, Spring}, html::Div};
+/// # use futures_lite::StreamExt;
+/// # let _ = async {
+/// # let div = Div::new();
+/// let mut scale = spring(0.5, 1.0, Spring::WOBBLY);
+/// while let Some(value) = scale.next().await {
+///     div.style()
+///         .set_property("transform", &format!("scale({value})"))
+///         .ok();
+/// }
+/// # };
+/// ```
+#[must_use = "the returned object is a Stream that does nothing unless polled"]
+pub fn spring(from: f64, to: f64, config: Spring) -> SpringAnimation {
+    SpringAnimation {
+        frames: animation_frames(),
+        state: SpringState {
+            config,
+            value: from,
+            velocity: 0.0,
+            target: to,
+        },
+        last_frame: None,
+        done: false,
+    }
+}
+
+impl SpringAnimation {
+    /// Change the value the spring is moving toward.
+    ///
+    /// If the Stream has already ended, it will start again from where it stopped.
+    pub fn set_target(&mut self, target: f64) {
+        self.state.target = target;
+        self.last_frame = None;
+        self.done = false;
+    }
+    /// The latest value of the animation.
+    pub fn value(&self) -> f64 {
+        self.state.value
+    }
+    /// The current velocity of the animation, in units per second.
+    pub fn velocity(&self) -> f64 {
+        self.state.velocity
+    }
+}
+
+impl Stream for SpringAnimation {
+    type Item = f64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        if this.last_frame.is_none() && prefers_reduced_motion() {
+            this.state.settle();
+            this.done = true;
+            return Poll::Ready(Some(this.state.value));
+        }
+        let Poll::Ready(Some(timestamp)) = Pin::new(&mut this.frames).poll_next(cx) else {
+            return Poll::Pending;
+        };
+        let elapsed_ms = this
+            .last_frame
+            .map_or(0.0, |last| (timestamp - last).max(0.0));
+        this.last_frame = Some(timestamp);
+        // Don't let a long pause (such as the tab being in the background)
+        // make the spring jump.
+        this.state.advance(elapsed_ms.min(MAX_FRAME_MS) / 1000.0);
+        if this.state.is_at_rest() {
+            this.state.settle();
+            this.done = true;
+        }
+        Poll::Ready(Some(this.state.value))
+    }
+}
+
+const MAX_FRAME_MS: f64 = 64.0;
+/// The largest step (in seconds) we integrate at once, for numerical stability.
+const MAX_STEP: f64 = 1.0 / 240.0;
+
+#[derive(Clone, Copy, Debug)]
+struct SpringState {
+    config: Spring,
+    value: f64,
+    velocity: f64,
+    target: f64,
+}
+
+impl SpringState {
+    /// Simulate the spring for `seconds`.
+    fn advance(&mut self, seconds: f64) {
+        let steps = (seconds / MAX_STEP).ceil().max(1.0);
+        let dt = seconds / steps;
+        for _ in 0..steps as u32 {
+            let Spring {
+                stiffness,
+                damping,
+                mass,
+                ..
+            } = self.config;
+            let force = -stiffness * (self.value - self.target) - damping * self.velocity;
+            // semi-implicit Euler: update velocity first, then position with the new velocity
+            self.velocity += force / mass * dt;
+            self.value += self.velocity * dt;
+        }
+    }
+    fn is_at_rest(&self) -> bool {
+        (self.value - self.target).abs() < self.config.precision
+            && self.velocity.abs() < self.config.precision
+    }
+    fn settle(&mut self) {
+        self.value = self.target;
+        self.velocity = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulate(config: Spring, from: f64, to: f64) -> (SpringState, Vec<f64>) {
+        let mut state = SpringState {
+            config,
+            value: from,
+            velocity: 0.0,
+            target: to,
+        };
+        let mut values = Vec::new();
+        for _ in 0..60 * 10 {
+            state.advance(1.0 / 60.0);
+            values.push(state.value);
+            if state.is_at_rest() {
+                break;
+            }
+        }
+        (state, values)
+    }
+
+    #[test]
+    fn comes_to_rest() {
+        for config in [
+            Spring::default(),
+            Spring::GENTLE,
+            Spring::WOBBLY,
+            Spring::STIFF,
+        ] {
+            let (state, _) = simulate(config, 0.0, 100.0);
+            assert!(state.is_at_rest(), "{config:?} did not come to rest");
+            assert!((state.value - 100.0).abs() < config.precision);
+        }
+    }
+
+    #[test]
+    fn wobbly_overshoots() {
+        let (_, values) = simulate(Spring::WOBBLY, 0.0, 100.0);
+        assert!(values.iter().any(|&v| v > 100.0));
+    }
+
+    #[test]
+    fn overdamped_does_not_overshoot() {
+        let config = Spring {
+            damping: 60.0,
+            ..Spring::default()
+        };
+        let (_, values) = simulate(config, 0.0, 100.0);
+        assert!(values.iter().all(|&v| v <= 100.0));
+    }
+
+    #[test]
+    fn zero_elapsed_time() {
+        let mut state = SpringState {
+            config: Spring::default(),
+            value: 0.0,
+            velocity: 0.0,
+            target: 1.0,
+        };
+        state.advance(0.0);
+        assert_eq!(state.value, 0.0);
+        assert_eq!(state.velocity, 0.0);
+    }
+}
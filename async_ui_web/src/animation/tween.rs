@@ -0,0 +1,96 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_lite::Stream;
+
+use super::{animation_frames, prefers_reduced_motion, AnimationFrames};
+
+/// A [Stream] yielding one value per frame as it moves from one number to another.
+///
+/// Use [tween] to get this struct.
+pub struct Tween {
+    frames: AnimationFrames,
+    from: f64,
+    to: f64,
+    duration_ms: f64,
+    easing: Box<dyn Fn(f64) -> f64>,
+    start: Option<f64>,
+    done: bool,
+}
+
+/// Move from `from` to `to` over `duration`, yielding the value at every frame.
+///
+/// `easing` maps the elapsed fraction of the duration (from 0 to 1) to the
+/// fraction of the distance traveled. See the [easing][super::easing] module
+/// for common choices.
+///
+/// The first value yielded is `from` and the last is `to`, after which the
+/// Stream ends. If [reduced motion][super::prefers_reduced_motion] is
+/// preferred, the Stream yields only `to`.
+///
+/// ```
+/// # use async_ui_web::{animation::{easing, tween}, html::Div};
+/// # use futures_lite::StreamExt;
+/// # use std::time::Duration;
+/// # let _ = async {
+/// # let div = Div::new();
+/// let mut slide = tween(-100.0, 0.0, Duration::from_millis(250), easing::ease_in_out);
+/// while let Some(x) = slide.next().await {
+///     div.style()
+///         .set_property("transform", &format!("translateX({x}px)"))
+///         .ok();
+/// }
+/// # };
+/// ```
+///
+/// Dropping the Stream stops the animation.
+#[must_use = "the returned object is a Stream that does nothing unless polled"]
+pub fn tween(
+    from: f64,
+    to: f64,
+    duration: Duration,
+    easing: impl Fn(f64) -> f64 + 'static,
+) -> Tween {
+    Tween {
+        frames: animation_frames(),
+        from,
+        to,
+        duration_ms: duration.as_secs_f64() * 1000.0,
+        easing: Box::new(easing),
+        start: None,
+        done: false,
+    }
+}
+
+impl Stream for Tween {
+    type Item = f64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        if this.start.is_none() && prefers_reduced_motion() {
+            this.done = true;
+            return Poll::Ready(Some(this.to));
+        }
+        let Poll::Ready(Some(timestamp)) = Pin::new(&mut this.frames).poll_next(cx) else {
+            return Poll::Pending;
+        };
+        let start = *this.start.get_or_insert(timestamp);
+        let progress = if this.duration_ms > 0.0 {
+            ((timestamp - start) / this.duration_ms).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        if progress >= 1.0 {
+            this.done = true;
+            return Poll::Ready(Some(this.to));
+        }
+        let eased = (this.easing)(progress);
+        Poll::Ready(Some(this.from + (this.to - this.from) * eased))
+    }
+}
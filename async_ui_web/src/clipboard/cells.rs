@@ -0,0 +1,164 @@
+//! Tab-separated cell ranges, in the dialect spreadsheets put on the clipboard.
+
+/// Format rows of cells as tab-separated values.
+///
+/// Cells containing tabs, line breaks, or double quotes are quoted,
+/// with double quotes doubled, so they survive a round trip through
+/// spreadsheet applications. Rows end with `\n`, except the last.
+pub fn to_tsv<R, C>(rows: R) -> String
+where
+    R: IntoIterator,
+    R::Item: IntoIterator<Item = C>,
+    C: AsRef<str>,
+{
+    let mut out = String::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for (j, cell) in row.into_iter().enumerate() {
+            if j > 0 {
+                out.push('\t');
+            }
+            let cell = cell.as_ref();
+            if cell.contains(['\t', '\n', '\r', '"']) {
+                out.push('"');
+                out.push_str(&cell.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(cell);
+            }
+        }
+    }
+    out
+}
+
+/// Parse tab-separated values into rows of cells.
+///
+/// Accepts what [to_tsv] produces, as well as `\r\n` line endings and a
+/// trailing line break (which spreadsheets usually add). Rows may have
+/// different numbers of cells. Empty input gives no rows.
+pub fn from_tsv(text: &str) -> Vec<Vec<String>> {
+    let text = text
+        .strip_suffix("\r\n")
+        .or_else(|| text.strip_suffix('\n'))
+        .unwrap_or(text);
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut chars = text.chars().peekable();
+    // whether we are inside a quoted cell
+    let mut quoted = false;
+    let mut at_cell_start = true;
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => quoted = false,
+                c => cell.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if at_cell_start => {
+                quoted = true;
+                at_cell_start = false;
+                continue;
+            }
+            '\t' => row.push(std::mem::take(&mut cell)),
+            '\r' if chars.peek() == Some(&'\n') => continue,
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => {
+                cell.push(c);
+                at_cell_start = false;
+                continue;
+            }
+        }
+        at_cell_start = true;
+    }
+    row.push(cell);
+    rows.push(row);
+    rows
+}
+
+/// Format rows of cells as an HTML table, which rich-text editors and
+/// spreadsheets paste as a table.
+pub fn to_html_table<R, C>(rows: R) -> String
+where
+    R: IntoIterator,
+    R::Item: IntoIterator<Item = C>,
+    C: AsRef<str>,
+{
+    let mut out = String::from("<table>");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            out.push_str("<td>");
+            for c in cell.as_ref().chars() {
+                match c {
+                    '&' => out.push_str("&amp;"),
+                    '<' => out.push_str("&lt;"),
+                    '>' => out.push_str("&gt;"),
+                    '"' => out.push_str("&quot;"),
+                    '\n' => out.push_str("<br>"),
+                    c => out.push(c),
+                }
+            }
+            out.push_str("</td>");
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsv_round_trip() {
+        let rows = vec![
+            vec!["a", "b", ""],
+            vec!["with\ttab", "with \"quotes\"", "multi\nline"],
+            vec!["plain"],
+        ];
+        let tsv = to_tsv(&rows);
+        assert_eq!(
+            tsv,
+            "a\tb\t\n\"with\ttab\"\t\"with \"\"quotes\"\"\"\t\"multi\nline\"\nplain"
+        );
+        assert_eq!(from_tsv(&tsv), rows);
+    }
+
+    #[test]
+    fn tsv_from_spreadsheet() {
+        assert_eq!(
+            from_tsv("1\t2\r\n3\t4\r\n"),
+            [vec!["1", "2"], vec!["3", "4"]]
+        );
+        assert_eq!(from_tsv("single"), [vec!["single"]]);
+        assert_eq!(from_tsv("\t"), [vec!["", ""]]);
+        assert!(from_tsv("").is_empty());
+        // quotes in the middle of a cell are literal
+        assert_eq!(from_tsv("5\" disk"), [vec!["5\" disk"]]);
+    }
+
+    #[test]
+    fn html_table() {
+        assert_eq!(
+            to_html_table([["a<b", "x"], ["1 & 2", "line\nbreak"]]),
+            "<table><tr><td>a&lt;b</td><td>x</td></tr>\
+             <tr><td>1 &amp; 2</td><td>line<br>break</td></tr></table>"
+        );
+    }
+}
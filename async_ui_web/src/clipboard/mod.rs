@@ -0,0 +1,306 @@
+//! The system clipboard.
+//!
+//! There are two ways to get at the clipboard.
+//!
+//! *   The async functions ([write_text], [read_text], [write], and [read])
+//!     use the [Clipboard API](https://developer.mozilla.org/en-US/docs/Web/API/Clipboard_API).
+//!     They can be called at any time, but the browser may ask the user for permission,
+//!     or refuse outright when the page isn't focused or not served over HTTPS.
+//! *   The `copy`, `cut`, and `paste` events (such as
+//!     [until_paste][crate::event_traits::EmitElementEvent::until_paste])
+//!     let you handle the user's own copy and paste (with the keyboard or the context menu).
+//!     These are always allowed, and give access to all the data types on the clipboard.
+//!     Turn each event into a [ClipboardTransfer] to get at the data.
+//!
+//! ```
+//! # use async_ui_web::{clipboard::{self, ClipboardTransfer}, html::Div, prelude_traits::*};
+//! # use futures_lite::StreamExt;
+//! # let _ = async {
+//! clipboard::write_text("copied!").await.ok();
+//!
+//! let grid = Div::new();
+//! let mut pastes = grid.until_paste().map(ClipboardTransfer::from);
+//! while let Some(paste) = pastes.next().await {
+//!     let cells: Vec<Vec<String>> = paste.cells();
+//!     // put the cells into the grid
+//! }
+//! # };
+//! ```
+
+mod cells;
+
+use std::fmt::Display;
+
+use async_ui_web_core::window::WINDOW;
+use async_ui_web_html::files::FileHandle;
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, ClipboardEvent, DataTransfer};
+
+pub use cells::{from_tsv, to_html_table, to_tsv};
+
+// Declared here rather than taken from web-sys, where these were long
+// behind `web_sys_unstable_apis`.
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = Clipboard)]
+    type JsClipboard;
+    #[wasm_bindgen(method, js_name = writeText)]
+    fn write_text(this: &JsClipboard, data: &str) -> js_sys::Promise;
+    #[wasm_bindgen(method, js_name = readText)]
+    fn read_text(this: &JsClipboard) -> js_sys::Promise;
+    #[wasm_bindgen(method)]
+    fn write(this: &JsClipboard, items: &js_sys::Array) -> js_sys::Promise;
+    #[wasm_bindgen(method)]
+    fn read(this: &JsClipboard) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = ClipboardItem)]
+    type JsClipboardItem;
+    #[wasm_bindgen(constructor, catch, js_class = ClipboardItem)]
+    fn new(items: &js_sys::Object) -> Result<JsClipboardItem, JsValue>;
+    #[wasm_bindgen(method, getter)]
+    fn types(this: &JsClipboardItem) -> js_sys::Array;
+    #[wasm_bindgen(method, js_name = getType)]
+    fn get_type(this: &JsClipboardItem, mime: &str) -> js_sys::Promise;
+}
+
+/// Why a clipboard operation failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// The browser doesn't have the Clipboard API, or doesn't allow it here
+    /// (it needs a secure context, such as HTTPS or localhost).
+    Unsupported,
+    /// The user or the browser denied access, for example because the page
+    /// isn't focused.
+    NotAllowed,
+    /// The clipboard doesn't have data of the requested type.
+    NotFound,
+    /// Any other error, with its message.
+    Other(String),
+}
+
+impl Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported => f.write_str("the clipboard is not available"),
+            Self::NotAllowed => f.write_str("clipboard access was denied"),
+            Self::NotFound => f.write_str("no clipboard data of the requested type"),
+            Self::Other(message) => write!(f, "clipboard operation failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+impl From<JsValue> for ClipboardError {
+    fn from(value: JsValue) -> Self {
+        let get = |key: &str| {
+            js_sys::Reflect::get(&value, &key.into())
+                .ok()
+                .and_then(|v| v.as_string())
+        };
+        match get("name").as_deref() {
+            Some("NotAllowedError") | Some("SecurityError") => Self::NotAllowed,
+            Some("NotFoundError") | Some("DataError") => Self::NotFound,
+            _ => Self::Other(get("message").unwrap_or_else(|| format!("{value:?}"))),
+        }
+    }
+}
+
+fn clipboard() -> Result<JsClipboard, ClipboardError> {
+    let navigator = WINDOW.with(|window| window.navigator());
+    let clipboard = js_sys::Reflect::get(&navigator, &"clipboard".into())?;
+    if clipboard.is_undefined() {
+        Err(ClipboardError::Unsupported)
+    } else {
+        Ok(clipboard.unchecked_into())
+    }
+}
+
+/// Put the given text on the clipboard.
+pub async fn write_text(text: &str) -> Result<(), ClipboardError> {
+    JsFuture::from(clipboard()?.write_text(text)).await?;
+    Ok(())
+}
+
+/// Get the text on the clipboard.
+pub async fn read_text() -> Result<String, ClipboardError> {
+    let text = JsFuture::from(clipboard()?.read_text()).await?;
+    text.as_string().ok_or(ClipboardError::NotFound)
+}
+
+/// Put the given items on the clipboard, replacing what was there.
+///
+/// Browsers generally support only one item, and only some types
+/// (`text/plain`, `text/html`, and `image/png` are widely supported).
+pub async fn write(items: &[ClipboardItem]) -> Result<(), ClipboardError> {
+    let clipboard = clipboard()?;
+    let array = js_sys::Array::new();
+    for item in items {
+        array.push(&item.to_js()?.into());
+    }
+    JsFuture::from(clipboard.write(&array)).await?;
+    Ok(())
+}
+
+/// Get the items on the clipboard.
+pub async fn read() -> Result<Vec<ClipboardContent>, ClipboardError> {
+    let items: js_sys::Array = JsFuture::from(clipboard()?.read()).await?.unchecked_into();
+    Ok(items
+        .iter()
+        .map(|item| ClipboardContent {
+            item: item.unchecked_into(),
+        })
+        .collect())
+}
+
+/// Data to put on the clipboard with [write]: the same content in one or more types.
+///
+/// ```
+/// # use async_ui_web::clipboard::{self, ClipboardItem};
+/// # let _ = async {
+/// let item = ClipboardItem::new()
+///     .with_text("text/plain", "bold")
+///     .with_text("text/html", "<b>bold</b>");
+/// clipboard::write(&[item]).await.ok();
+/// # };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClipboardItem {
+    entries: Vec<(String, JsValue)>,
+}
+
+impl ClipboardItem {
+    /// Create an item with no data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add text data of the given MIME type.
+    pub fn with_text(mut self, mime_type: &str, text: &str) -> Self {
+        self.entries.push((mime_type.to_owned(), text.into()));
+        self
+    }
+    /// Add binary data, such as an image. The MIME type is the [Blob]'s.
+    pub fn with_blob(mut self, blob: Blob) -> Self {
+        self.entries.push((blob.type_(), blob.into()));
+        self
+    }
+    fn to_js(&self) -> Result<JsClipboardItem, ClipboardError> {
+        let record = js_sys::Object::new();
+        for (mime_type, data) in &self.entries {
+            js_sys::Reflect::set(&record, &mime_type.into(), data)?;
+        }
+        JsClipboardItem::new(&record).map_err(|_| ClipboardError::Unsupported)
+    }
+}
+
+/// An item read from the clipboard with [read].
+pub struct ClipboardContent {
+    item: JsClipboardItem,
+}
+
+impl ClipboardContent {
+    /// The MIME types this item has data for.
+    pub fn types(&self) -> Vec<String> {
+        self.item
+            .types()
+            .iter()
+            .filter_map(|t| t.as_string())
+            .collect()
+    }
+    /// Get the data of the given type.
+    pub async fn get_blob(&self, mime_type: &str) -> Result<Blob, ClipboardError> {
+        let blob = JsFuture::from(self.item.get_type(mime_type)).await?;
+        blob.dyn_into().map_err(|_| ClipboardError::NotFound)
+    }
+    /// Get the data of the given type, as text.
+    pub async fn get_text(&self, mime_type: &str) -> Result<String, ClipboardError> {
+        let text = JsFuture::from(self.get_blob(mime_type).await?.text()).await?;
+        text.as_string().ok_or(ClipboardError::NotFound)
+    }
+}
+
+/// A `copy`, `cut`, or `paste` event, for reading or writing the data it carries.
+///
+/// Get one from the event with [From]: `el.until_paste().map(ClipboardTransfer::from)`.
+///
+/// The data must be read (for paste) or written (for copy and cut) right away
+/// when the event arrives, before `await`ing anything else.
+/// The browser stops allowing access once the event is over.
+pub struct ClipboardTransfer {
+    event: ClipboardEvent,
+}
+
+impl ClipboardTransfer {
+    /// The underlying event.
+    pub fn event(&self) -> &ClipboardEvent {
+        &self.event
+    }
+    fn data(&self) -> Option<DataTransfer> {
+        self.event.clipboard_data()
+    }
+    /// The MIME types available (for paste).
+    pub fn types(&self) -> Vec<String> {
+        self.data()
+            .map(|data| data.types().iter().filter_map(|t| t.as_string()).collect())
+            .unwrap_or_default()
+    }
+    /// The data of the given type, if any (for paste).
+    pub fn get(&self, mime_type: &str) -> Option<String> {
+        self.data()?
+            .get_data(mime_type)
+            .ok()
+            .filter(|data| !data.is_empty())
+    }
+    /// The plain text pasted, if any.
+    pub fn text(&self) -> Option<String> {
+        self.get("text/plain")
+    }
+    /// The pasted text parsed as a range of cells (see [from_tsv]).
+    /// Empty if no text was pasted.
+    pub fn cells(&self) -> Vec<Vec<String>> {
+        self.text().map(|text| from_tsv(&text)).unwrap_or_default()
+    }
+    /// The files pasted, if any.
+    pub fn files(&self) -> Vec<FileHandle> {
+        let Some(files) = self.data().and_then(|data| data.files()) else {
+            return Vec::new();
+        };
+        (0..files.length())
+            .filter_map(|i| files.get(i))
+            .map(FileHandle::from)
+            .collect()
+    }
+    /// Set the data of the given type (for copy and cut).
+    ///
+    /// This prevents the browser's default handling, so only the data you
+    /// set ends up on the clipboard. For cut, removing the selection is then up to you.
+    pub fn set(&self, mime_type: &str, data: &str) {
+        if let Some(transfer) = self.data() {
+            transfer.set_data(mime_type, data).ok();
+            self.event.prevent_default();
+        }
+    }
+    /// Set the plain text (for copy and cut). See [set][Self::set].
+    pub fn set_text(&self, text: &str) {
+        self.set("text/plain", text)
+    }
+    /// Put a range of cells on the clipboard (for copy and cut), as tab-separated
+    /// text and as an HTML table, so it pastes well both in spreadsheets and in
+    /// rich-text editors. See [set][Self::set].
+    pub fn set_cells<R, C>(&self, rows: R)
+    where
+        R: IntoIterator + Clone,
+        R::Item: IntoIterator<Item = C>,
+        C: AsRef<str>,
+    {
+        self.set("text/plain", &to_tsv(rows.clone()));
+        self.set("text/html", &to_html_table(rows));
+    }
+}
+
+impl From<ClipboardEvent> for ClipboardTransfer {
+    fn from(event: ClipboardEvent) -> Self {
+        Self { event }
+    }
+}
@@ -0,0 +1,427 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Display,
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    time::Duration,
+};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::combinators::{join, race};
+use async_ui_web_html::{
+    events::{EmitElementEvent, EmitHtmlElementEvent},
+    nodes::{Div, Input},
+};
+use futures_lite::{Stream, StreamExt};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlElement, Node};
+
+use super::DynamicSlot;
+use crate::{
+    popover::{Align, Placement, Popover, Side},
+    timer::sleep,
+    unique_id::unique_id,
+};
+
+type OptionsFuture<'c> = Pin<Box<dyn Future<Output = ()> + 'c>>;
+
+/**
+A text input with a list of suggestions, fetched as the user types.
+
+```
+# use async_ui_web::{components::Combobox, prelude_traits::*};
+# async fn search_cities(query: String) -> Vec<String> { vec![query] }
+# let _ = async {
+let combobox = Combobox::<String>::new();
+combobox.set_placeholder("City");
+combobox
+    .render(
+        search_cities,
+        |city| {
+            let city = city.clone();
+            async move { city.render().await }
+        },
+    )
+    .await;
+# };
+```
+
+Suggestions come from an async function of the text typed. It is called once typing
+pauses for a moment (see [set_debounce][Self::set_debounce]); if the text changes while
+it is running, its future is dropped, so stale results never show up.
+
+The suggestions are shown in a [Popover] below the input. The user can pick one
+by clicking it, or by highlighting it with the Up and Down arrow keys and pressing Enter.
+Escape closes the list. The picked suggestion is the [value][Self::value], and its
+[Display] text goes into the input.
+
+By default the user can also type text that isn't a suggestion: typing clears the value,
+and the text is available with [HtmlInputElement::value][web_sys::HtmlInputElement::value].
+In [strict][Self::set_strict] mode, only suggestions are accepted.
+
+The input has the ARIA `combobox` role, and the list is a `listbox` whose options are
+linked to the input with `aria-activedescendant` as they are highlighted
+(options have `aria-selected="true"` while highlighted, for styling).
+
+This type [Deref]s to [Input], so all the HTML methods are available on it.
+ */
+pub struct Combobox<T: Clone + Display> {
+    input: Input,
+    popover: Popover,
+    listbox: Div,
+    inner: RefCell<Inner<T>>,
+    value: ReactiveCell<Option<T>>,
+    strict: Cell<bool>,
+    debounce: Cell<Duration>,
+    id_prefix: String,
+}
+
+struct Inner<T> {
+    items: Vec<T>,
+    options: Vec<HtmlElement>,
+    highlighted: Option<usize>,
+}
+
+impl<T: Clone + Display> Default for Combobox<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Deref for Combobox<T> {
+    type Target = Input;
+    fn deref(&self) -> &Self::Target {
+        &self.input
+    }
+}
+
+impl<T: Clone + Display> Combobox<T> {
+    /// Create a new combobox, with no value.
+    pub fn new() -> Self {
+        let id_prefix = unique_id("combobox");
+        let input = Input::new();
+        let listbox = Div::new();
+        let listbox_id = format!("{id_prefix}-listbox");
+        listbox.set_id(&listbox_id);
+        listbox.set_attribute("role", "listbox").unwrap_throw();
+        for (name, value) in [
+            ("role", "combobox"),
+            ("aria-autocomplete", "list"),
+            ("aria-expanded", "false"),
+            ("aria-controls", &listbox_id),
+            ("autocomplete", "off"),
+        ] {
+            input.set_attribute(name, value).unwrap_throw();
+        }
+        let popover = Popover::new();
+        popover.set_placement(Placement::new(Side::Bottom, Align::Start));
+        popover.set_offset(2.0);
+        let combobox = Self {
+            input,
+            popover,
+            listbox,
+            inner: RefCell::new(Inner {
+                items: Vec::new(),
+                options: Vec::new(),
+                highlighted: None,
+            }),
+            value: ReactiveCell::new(None),
+            strict: Cell::new(false),
+            debounce: Cell::new(Duration::from_millis(200)),
+            id_prefix,
+        };
+        combobox.set_open(false);
+        combobox
+    }
+    /// Only accept suggestions, not free text. Defaults to `false`.
+    ///
+    /// In strict mode, typing doesn't clear the value, and when the input loses focus,
+    /// its text goes back to that of the value (or is cleared if there is no value).
+    pub fn set_strict(&self, strict: bool) {
+        self.strict.set(strict);
+    }
+    /// Set how long typing must pause before suggestions are fetched.
+    /// Defaults to 200 milliseconds.
+    pub fn set_debounce(&self, debounce: Duration) {
+        self.debounce.set(debounce);
+    }
+    /// The suggestion the user picked, if any.
+    pub fn value(&self) -> Option<T> {
+        self.value.borrow().clone()
+    }
+    /// Set the value, and put its text in the input (or clear the input if `None`).
+    pub fn set_value(&self, value: Option<T>) {
+        self.input
+            .set_value(&value.as_ref().map(T::to_string).unwrap_or_default());
+        *self.value.borrow_mut() = value;
+    }
+    /// Get a [Stream] that fires every time the value changes,
+    /// whether by the user or by [set_value][Self::set_value].
+    pub fn until_change(&self) -> impl Future<Output = ()> + Stream<Item = ()> + '_ {
+        self.value.until_change()
+    }
+    /// Put the combobox on the screen.
+    ///
+    /// `source` is called with the text in the input to get suggestions, and
+    /// `render_item` is called for each suggestion to get the future to render
+    /// in its option.
+    ///
+    /// This async method never completes.
+    pub async fn render<S, SF, R, RF>(&self, source: S, render_item: R)
+    where
+        S: Fn(String) -> SF,
+        SF: Future<Output = Vec<T>>,
+        R: Fn(&T) -> RF,
+        RF: Future<Output = ()>,
+    {
+        let slot = DynamicSlot::<OptionsFuture<'_>>::new();
+        join((
+            self.input.render(),
+            self.popover
+                .render(&self.input, self.listbox.render(slot.render())),
+            self.suggest(&source, &slot, &render_item),
+            self.handle_keys(),
+            self.handle_clicks(),
+            self.handle_blur(),
+        ))
+        .await;
+    }
+    async fn suggest<'c, SF: Future<Output = Vec<T>>, RF: Future<Output = ()> + 'c>(
+        &'c self,
+        source: &impl Fn(String) -> SF,
+        slot: &DynamicSlot<OptionsFuture<'c>>,
+        render_item: &'c impl Fn(&T) -> RF,
+    ) where
+        T: 'c,
+    {
+        let mut inputs = self.input.until_input();
+        let mut typed = false;
+        loop {
+            if !typed {
+                inputs.next().await;
+                self.text_changed();
+            }
+            typed = false;
+            let waited = race((
+                async {
+                    sleep(self.debounce.get()).await;
+                    true
+                },
+                async {
+                    inputs.next().await;
+                    false
+                },
+            ))
+            .await;
+            if !waited {
+                self.text_changed();
+                typed = true;
+                continue;
+            }
+            // dropping the request's future cancels it
+            let items = race((async { Some(source(self.input.value()).await) }, async {
+                inputs.next().await;
+                None
+            }))
+            .await;
+            match items {
+                Some(items) => self.show_items(items, slot, render_item),
+                None => {
+                    self.text_changed();
+                    typed = true;
+                }
+            }
+        }
+    }
+    fn text_changed(&self) {
+        if !self.strict.get() && self.value.borrow().is_some() {
+            *self.value.borrow_mut() = None;
+        }
+    }
+    fn show_items<'c, RF: Future<Output = ()> + 'c>(
+        &self,
+        items: Vec<T>,
+        slot: &DynamicSlot<OptionsFuture<'c>>,
+        render_item: &'c impl Fn(&T) -> RF,
+    ) where
+        T: 'c,
+    {
+        let options: Vec<Div> = (0..items.len())
+            .map(|index| {
+                let option = Div::new();
+                option.set_id(&format!("{}-option-{index}", self.id_prefix));
+                option.set_attribute("role", "option").unwrap_throw();
+                option
+                    .set_attribute("aria-selected", "false")
+                    .unwrap_throw();
+                option
+            })
+            .collect();
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.options = options
+                .iter()
+                .map(|option| option.element.clone().into())
+                .collect();
+            inner.items = items.clone();
+        }
+        self.highlight(None);
+        self.set_open(!items.is_empty());
+        slot.set_future(Box::pin(async move {
+            join(
+                options
+                    .iter()
+                    .zip(&items)
+                    .map(|(option, item)| option.render(render_item(item)))
+                    .collect::<Vec<_>>(),
+            )
+            .await;
+        }));
+    }
+    fn is_open(&self) -> bool {
+        self.input.get_attribute("aria-expanded").as_deref() == Some("true")
+    }
+    fn set_open(&self, open: bool) {
+        self.input
+            .set_attribute("aria-expanded", if open { "true" } else { "false" })
+            .unwrap_throw();
+        let style = self.popover.style();
+        if open {
+            style.remove_property("display").unwrap_throw();
+        } else {
+            style.set_property("display", "none").unwrap_throw();
+            self.highlight(None);
+        }
+    }
+    fn highlight(&self, index: Option<usize>) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(old) = inner.highlighted.and_then(|i| inner.options.get(i)) {
+            old.set_attribute("aria-selected", "false").unwrap_throw();
+        }
+        inner.highlighted = index;
+        match index.and_then(|i| inner.options.get(i)) {
+            Some(option) => {
+                option.set_attribute("aria-selected", "true").unwrap_throw();
+                option.scroll_into_view_with_bool(false);
+                self.input
+                    .set_attribute("aria-activedescendant", &option.id())
+                    .unwrap_throw();
+            }
+            None => {
+                self.input
+                    .remove_attribute("aria-activedescendant")
+                    .unwrap_throw();
+            }
+        }
+    }
+    fn select(&self, index: usize) {
+        let item = self.inner.borrow().items.get(index).cloned();
+        if let Some(item) = item {
+            self.set_open(false);
+            self.set_value(Some(item));
+        }
+    }
+    async fn handle_keys(&self) {
+        let mut keydowns = self.input.until_keydown();
+        while let Some(ev) = keydowns.next().await {
+            if ev.alt_key() || ev.ctrl_key() || ev.meta_key() {
+                continue;
+            }
+            let (len, highlighted) = {
+                let inner = self.inner.borrow();
+                (inner.items.len(), inner.highlighted)
+            };
+            match &*ev.key() {
+                "ArrowDown" | "ArrowUp" if len > 0 => {
+                    ev.prevent_default();
+                    if !self.is_open() {
+                        self.set_open(true);
+                    }
+                    self.highlight(step(highlighted, len, ev.key() == "ArrowDown"));
+                }
+                "Enter" if self.is_open() => {
+                    if let Some(index) = highlighted {
+                        ev.prevent_default();
+                        self.select(index);
+                    }
+                }
+                "Escape" if self.is_open() => {
+                    ev.prevent_default();
+                    self.set_open(false);
+                }
+                "Tab" => self.set_open(false),
+                _ => {}
+            }
+        }
+    }
+    async fn handle_clicks(&self) {
+        // keep focus in the input when an option is pressed
+        let mut mousedowns = self.listbox.until_mousedown();
+        let mut clicks = self.listbox.until_click();
+        loop {
+            let click = race((
+                async {
+                    mousedowns.next().await.unwrap_throw().prevent_default();
+                    None
+                },
+                async { Some(clicks.next().await.unwrap_throw()) },
+            ))
+            .await;
+            let Some(target) = click
+                .and_then(|ev| ev.target())
+                .and_then(|t| t.dyn_into::<Node>().ok())
+            else {
+                continue;
+            };
+            let index = self
+                .inner
+                .borrow()
+                .options
+                .iter()
+                .position(|option| option.contains(Some(&target)));
+            if let Some(index) = index {
+                self.select(index);
+            }
+        }
+    }
+    async fn handle_blur(&self) {
+        let mut blurs = self.input.until_blur();
+        while blurs.next().await.is_some() {
+            self.set_open(false);
+            if self.strict.get() {
+                let text = self.value().as_ref().map(T::to_string).unwrap_or_default();
+                self.input.set_value(&text);
+            }
+        }
+    }
+}
+
+/// Move the highlight one option down (or up), wrapping around.
+/// From no highlight, go to the first (or last) option.
+fn step(current: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current, forward) {
+        (None, true) => 0,
+        (None, false) => len - 1,
+        (Some(i), true) => (i + 1) % len,
+        (Some(i), false) => (i + len - 1) % len,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::step;
+
+    #[test]
+    fn highlight_steps() {
+        assert_eq!(step(None, 3, true), Some(0));
+        assert_eq!(step(None, 3, false), Some(2));
+        assert_eq!(step(Some(2), 3, true), Some(0));
+        assert_eq!(step(Some(0), 3, false), Some(2));
+        assert_eq!(step(Some(1), 3, true), Some(2));
+        assert_eq!(step(None, 0, true), None);
+    }
+}
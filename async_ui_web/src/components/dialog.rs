@@ -0,0 +1,205 @@
+use std::{cell::Cell, future::Future, ops::Deref};
+
+use async_ui_web_core::combinators::{join, race, UiFutureExt};
+use async_ui_web_html::{
+    events::{EmitElementEvent, EmitEvent},
+    nodes::{Button, Dialog as DialogNode, Div, Paragraph},
+};
+use futures_lite::StreamExt;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, EventTarget, HtmlDialogElement, HtmlElement, MouseEvent, Node};
+
+use crate::{animation::animation_frames, shortcuts::ShortcutRenderStr};
+
+/**
+A modal `<dialog>`, shown with
+[showModal](https://developer.mozilla.org/en-US/docs/Web/API/HTMLDialogElement/showModal).
+
+[show][Self::show] renders the dialog's content and completes with its result,
+so asking the user something is a single `await`.
+
+```
+# use async_ui_web::{components::Dialog, html::Button, join, race, prelude_traits::*};
+# let _ = async {
+let dialog = Dialog::new();
+let (save, discard) = (Button::new(), Button::new());
+let answer: Option<bool> = dialog
+    .show(
+        race((
+            async { save.until_click().await; true },
+            async { discard.until_click().await; false },
+        ))
+        .meanwhile(join((
+            "Save changes?".render(),
+            save.render("Save".render()),
+            discard.render("Discard".render()),
+        ))),
+    )
+    .await;
+// `None` if the user pressed Escape
+# };
+```
+
+For a simple yes/no question, there is [confirm_dialog].
+
+This type [Deref]s to [Dialog][DialogNode], so all the HTML methods are available on it.
+ */
+pub struct Dialog {
+    node: DialogNode,
+    dismissible: Cell<bool>,
+}
+
+impl Default for Dialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Dialog {
+    type Target = DialogNode;
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl Dialog {
+    /// Create a new dialog.
+    pub fn new() -> Self {
+        Self {
+            node: DialogNode::new(),
+            dismissible: Cell::new(true),
+        }
+    }
+    /// Whether the user can close the dialog without answering, by pressing Escape
+    /// or clicking the backdrop. Defaults to `true`.
+    pub fn set_dismissible(&self, dismissible: bool) {
+        self.dismissible.set(dismissible);
+    }
+    /// Render the given content in the dialog and open it as a modal.
+    ///
+    /// The returned future completes with `Some` of the content's output when the content
+    /// completes, or with `None` if the dialog is dismissed (see [set_dismissible][Self::set_dismissible])
+    /// or closed some other way, such as by a `<form method="dialog">`.
+    ///
+    /// The dialog closes when the returned future completes or is dropped.
+    pub async fn show<F: Future>(&self, content: F) -> Option<F::Output> {
+        let _close = CloseOnDrop(&self.node);
+        race((self.node.render(async { Some(content.await) }), async {
+            self.open().await;
+            self.until_dismissed().await;
+            None
+        }))
+        .await
+    }
+    async fn open(&self) {
+        let node: &Node = self.node.as_ref();
+        // `showModal` only works once the element is in the document.
+        while !node.is_connected() {
+            animation_frames().await;
+        }
+        if !self.node.open() {
+            // Throws only if the dialog is disconnected or already open.
+            self.node.show_modal().ok();
+        }
+    }
+    async fn until_dismissed(&self) {
+        let target: &EventTarget = self.node.as_ref();
+        let mut events = self
+            .node
+            .until_cancel()
+            .map(Dismissal::Cancel)
+            .or(target
+                .until_event::<Event>("close".into())
+                .map(|_| Dismissal::Closed))
+            .or(self.node.until_click().map(Dismissal::Click));
+        while let Some(event) = events.next().await {
+            match event {
+                Dismissal::Cancel(ev) => {
+                    // Don't let the browser close the dialog; we do that when `show` completes.
+                    ev.prevent_default();
+                    if self.dismissible.get() {
+                        return;
+                    }
+                }
+                Dismissal::Closed => return,
+                Dismissal::Click(ev) => {
+                    if self.dismissible.get() && self.is_backdrop_click(&ev) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+    /// Clicks on the backdrop are dispatched to the dialog itself,
+    /// but land outside its box.
+    fn is_backdrop_click(&self, ev: &MouseEvent) -> bool {
+        let element: &HtmlDialogElement = &self.node;
+        let on_dialog = ev
+            .target()
+            .and_then(|t| t.dyn_into::<HtmlDialogElement>().ok())
+            .is_some_and(|t| &t == element);
+        if !on_dialog {
+            return false;
+        }
+        let rect = element.get_bounding_client_rect();
+        let (x, y) = (ev.client_x() as f64, ev.client_y() as f64);
+        x < rect.left() || x > rect.right() || y < rect.top() || y > rect.bottom()
+    }
+}
+
+enum Dismissal {
+    Cancel(Event),
+    Closed,
+    Click(MouseEvent),
+}
+
+struct CloseOnDrop<'a>(&'a HtmlDialogElement);
+
+impl Drop for CloseOnDrop<'_> {
+    fn drop(&mut self) {
+        if self.0.open() {
+            self.0.close();
+        }
+    }
+}
+
+/// Ask the user a yes/no question in a modal [Dialog] with "OK" and "Cancel" buttons.
+///
+/// Completes with `true` if the user clicks "OK", and `false` if they click "Cancel"
+/// or dismiss the dialog.
+///
+/// ```
+/// # use async_ui_web::components::confirm_dialog;
+/// # let _ = async {
+/// if confirm_dialog("Delete this file?").await {
+///     // delete it
+/// }
+/// # };
+/// ```
+pub async fn confirm_dialog(message: &str) -> bool {
+    let dialog = Dialog::new();
+    let (ok, cancel) = (Button::new(), Button::new());
+    AsRef::<HtmlElement>::as_ref(&ok).set_autofocus(true).ok();
+    dialog
+        .show(
+            race((
+                async {
+                    ok.until_click().await;
+                    true
+                },
+                async {
+                    cancel.until_click().await;
+                    false
+                },
+            ))
+            .meanwhile(join((
+                Paragraph::new().render(message.render()),
+                Div::new().render(join((
+                    ok.render("OK".render()),
+                    cancel.render("Cancel".render()),
+                ))),
+            ))),
+        )
+        .await
+        .unwrap_or(false)
+}
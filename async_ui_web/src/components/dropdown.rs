@@ -9,10 +9,11 @@ use std::{
 
 use async_ui_web_html::{
     events::EmitHtmlElementEvent,
-    nodes::{Option as OptElem, Select},
+    nodes::{OptGroup, Option as OptElem, Select},
 };
-use futures_lite::Stream;
+use futures_lite::{Stream, StreamExt};
 use wasm_bindgen::UnwrapThrowExt;
+use web_sys::Node;
 
 use crate::NoChild;
 
@@ -49,6 +50,33 @@ use crate::NoChild;
 /// # };
 /// ```
 ///
+/// Options can also be sorted into `<optgroup>`s with [update_groups][Self::update_groups],
+/// [disabled][Self::set_option_disabled] individually, and preceded by a
+/// [placeholder][Self::set_placeholder]. With [set_multiple][Self::set_multiple],
+/// the user can select several options; use [values][Self::values] to get them.
+///
+/// ```
+/// # use async_ui_web::{components::Dropdown, prelude_traits::*, join};
+/// # use futures_lite::StreamExt;
+/// # let _ = async {
+/// let dropdown = Dropdown::<&str>::new();
+/// dropdown.set_multiple(true);
+/// dropdown.update_groups([
+///     ("Fruits", vec![("apple", "Apple"), ("banana", "Banana")]),
+///     ("Vegetables", vec![("carrot", "Carrot")]),
+/// ]);
+/// dropdown.set_option_disabled(&"banana", true);
+///
+/// let mut selections = dropdown.selected_values_stream();
+/// join((dropdown.render(), async {
+///     while let Some(selected) = selections.next().await {
+///         let selected: Vec<&str> = selected;
+///     }
+/// }))
+/// .await;
+/// # };
+/// ```
+///
 /// This type [Deref]s to [Select] and [HtmlSelectElement][web_sys::HtmlSelectElement],
 /// so you can use all the HTML methods (such as [set_disabled][web_sys::HtmlSelectElement::set_disabled]) on it.
 pub struct Dropdown<O: Eq + Hash + Clone> {
@@ -71,8 +99,28 @@ impl<O: Eq + Hash + Clone> Deref for Dropdown<O> {
 
 struct Inner<O> {
     selected: Option<O>,
+    /// All the options, in display order.
     prev_opts_list: Vec<O>,
     map: HashMap<O, OptElem>,
+    groups: HashMap<String, OptGroup>,
+    placeholder: Option<OptElem>,
+}
+
+/// Put `children` into `parent` in the given order, moving as few nodes as possible.
+/// Other children of `parent` are removed.
+fn place_children<'a>(parent: &Node, children: impl IntoIterator<Item = &'a Node>) {
+    let mut next = parent.first_child();
+    for child in children {
+        if next.as_ref() == Some(child) {
+            next = child.next_sibling();
+        } else {
+            parent.insert_before(child, next.as_ref()).unwrap_throw();
+        }
+    }
+    while let Some(extra) = next {
+        next = extra.next_sibling();
+        parent.remove_child(&extra).unwrap_throw();
+    }
 }
 
 impl<O: Eq + Hash + Clone> Dropdown<O> {
@@ -86,6 +134,8 @@ impl<O: Eq + Hash + Clone> Dropdown<O> {
                 selected: None,
                 prev_opts_list: vec![],
                 map: HashMap::new(),
+                groups: HashMap::new(),
+                placeholder: None,
             }),
         }
     }
@@ -101,17 +151,43 @@ impl<O: Eq + Hash + Clone> Dropdown<O> {
     ///
     /// If the given value is `None` or is not a valid option,
     /// then deselect the current selection.
+    ///
+    /// In [multiple][Self::set_multiple] mode, this deselects all other options.
     pub fn set_value(&self, opt: Option<O>) {
-        if let Some(opt_ref) = opt.as_ref() {
-            let inner = self.inner.borrow();
-            if let Some(elem) = inner.map.get(opt_ref) {
-                elem.set_selected(true);
-                drop(inner);
-                self.inner.borrow_mut().selected = opt;
-                return;
+        self.set_values(opt);
+    }
+    /// Select exactly the given options (for [multiple][Self::set_multiple] mode).
+    ///
+    /// Options that aren't in the menu are ignored.
+    /// In single-select mode, only the first valid option is selected.
+    pub fn set_values(&self, opts: impl IntoIterator<Item = O>) {
+        let mut inner = self.inner.borrow_mut();
+        let wanted: HashSet<O> = opts
+            .into_iter()
+            .filter(|opt| inner.map.contains_key(opt))
+            .collect();
+        let first = inner
+            .prev_opts_list
+            .iter()
+            .find(|opt| wanted.contains(*opt))
+            .cloned();
+        if self.select.multiple() {
+            for (opt, elem) in inner.map.iter() {
+                elem.set_selected(wanted.contains(opt));
             }
+        } else if let Some(elem) = first.as_ref().and_then(|opt| inner.map.get(opt)) {
+            elem.set_selected(true);
+        } else {
+            self.deselect(&inner);
+        }
+        inner.selected = first;
+    }
+    /// Clear the selection, selecting the placeholder if there is one.
+    fn deselect(&self, inner: &Inner<O>) {
+        match &inner.placeholder {
+            Some(placeholder) => placeholder.set_selected(true),
+            None => self.select.set_value(""),
         }
-        self.select.set_value("");
     }
     /// Set the options to be displayed in the dropdown.
     ///
@@ -120,50 +196,151 @@ impl<O: Eq + Hash + Clone> Dropdown<O> {
     /// you will get from [value()][Self::value] if the user selects it) and the
     /// `str` will be the displayed text.
     pub fn update_options<'t>(&'t self, new_opts: impl IntoIterator<Item = (O, &'t str)>) {
-        let current_value = self.value();
+        self.update(new_opts.into_iter().map(|(opt, text)| (None, opt, text)));
+    }
+    /// Set the options to be displayed in the dropdown, in `<optgroup>` sections.
+    ///
+    /// Argument must be an [IntoIterator] yielding the label of each group, and the
+    /// group's options in the same form as for [update_options][Self::update_options].
+    ///
+    /// If a label is given more than once, the options are all put in one group,
+    /// where the label first appears.
+    pub fn update_groups<'t, G: IntoIterator<Item = (O, &'t str)>>(
+        &'t self,
+        new_groups: impl IntoIterator<Item = (&'t str, G)>,
+    ) {
+        self.update(new_groups.into_iter().flat_map(|(label, opts)| {
+            opts.into_iter()
+                .map(move |(opt, text)| (Some(label), opt, text))
+        }));
+    }
+    fn update<'t>(&self, new_opts: impl Iterator<Item = (Option<&'t str>, O, &'t str)>) {
+        let current_values = self.values();
+        let multiple = self.select.multiple();
         let mut bm = self.inner.borrow_mut();
         let Inner {
             selected,
             prev_opts_list,
             map,
+            groups,
+            placeholder,
         } = &mut *bm;
-        *selected = current_value;
+        *selected = current_values.first().cloned();
 
-        let mut prev_opts_set = prev_opts_list.iter().collect::<HashSet<_>>();
-        let mut prev_opts_iter = prev_opts_list.iter().peekable();
+        // the direct children of the select, and of each group, in order
+        let mut top_level: Vec<Node> = placeholder
+            .iter()
+            .map(|p| p.element.clone().into())
+            .collect();
+        let mut grouped: Vec<(&'t str, Vec<Node>)> = Vec::new();
+        // groups are shared by label, so a label seen again adds to the same group
+        let mut group_index: HashMap<&'t str, usize> = HashMap::new();
+        // where each of `grouped` is among `top_level`
+        let mut positions: Vec<usize> = Vec::new();
+        // each option with the position among `top_level` of it or its group
         let mut new_opts_list = Vec::new();
-        for (opt, text) in new_opts {
-            let should_put = if prev_opts_set.remove(&opt) {
-                prev_opts_iter.next_if_eq(&&opt).is_none()
-            } else {
-                let elem = OptElem::new();
-                map.insert(opt.clone(), elem);
-                true
+        let mut new_opts_set = HashSet::new();
+        for (group, opt, text) in new_opts {
+            let elem = map.entry(opt.clone()).or_insert_with(OptElem::new);
+            elem.set_text_content(Some(text));
+            let node: Node = elem.element.clone().into();
+            let position = match group {
+                None => {
+                    top_level.push(node);
+                    top_level.len() - 1
+                }
+                Some(label) => {
+                    let index = *group_index.entry(label).or_insert_with(|| {
+                        let group = groups.entry(label.to_owned()).or_insert_with(|| {
+                            let group = OptGroup::new();
+                            group.set_label(label);
+                            group
+                        });
+                        top_level.push(group.element.clone().into());
+                        positions.push(top_level.len() - 1);
+                        grouped.push((label, Vec::new()));
+                        grouped.len() - 1
+                    });
+                    grouped[index].1.push(node);
+                    positions[index]
+                }
             };
-            if should_put {
-                self.select
-                    .insert_before(
-                        map.get(&opt).unwrap(),
-                        prev_opts_iter
-                            .peek()
-                            .map(|next| map.get(next).unwrap().as_ref()),
-                    )
-                    .unwrap_throw();
-            }
-            map.get(&opt).unwrap().set_text_content(Some(text));
-            new_opts_list.push(opt);
+            new_opts_set.insert(opt.clone());
+            new_opts_list.push((position, opt));
         }
+        // display order; the sort is stable, so options in a group keep their order
+        new_opts_list.sort_by_key(|(position, _)| *position);
+        map.retain(|opt, _| new_opts_set.contains(opt));
+        groups.retain(|label, _| group_index.contains_key(label.as_str()));
 
-        for opt in prev_opts_set.drain() {
-            self.select
-                .remove_child(map.remove(opt).unwrap().as_ref())
-                .unwrap_throw();
+        place_children(&self.select, &top_level);
+        for (label, children) in &grouped {
+            place_children(&groups[*label], children);
         }
-        *prev_opts_list = new_opts_list;
-        if let Some(sel) = selected.as_ref().and_then(|k| map.get(k)) {
+        *prev_opts_list = new_opts_list.into_iter().map(|(_, opt)| opt).collect();
+
+        if multiple {
+            let current: HashSet<_> = current_values.into_iter().collect();
+            for (opt, elem) in map.iter() {
+                elem.set_selected(current.contains(opt));
+            }
+        } else if let Some(sel) = selected.as_ref().and_then(|k| map.get(k)) {
             sel.set_selected(true);
         } else {
-            self.select.set_value("");
+            drop(bm);
+            self.deselect(&self.inner.borrow());
+        }
+    }
+    /// Show a disabled option with the given text first, selected when nothing else is.
+    /// Pass `None` to remove it.
+    ///
+    /// This is for single-select mode; it is hidden in [multiple][Self::set_multiple] mode.
+    pub fn set_placeholder(&self, text: Option<&str>) {
+        let mut inner = self.inner.borrow_mut();
+        match (text, &inner.placeholder) {
+            (Some(text), Some(placeholder)) => placeholder.set_text_content(Some(text)),
+            (Some(text), None) => {
+                let placeholder = OptElem::new();
+                placeholder.set_text_content(Some(text));
+                placeholder.set_value("");
+                placeholder.set_disabled(true);
+                placeholder.set_hidden(self.select.multiple());
+                let had_selection = self.select.selected_index() >= 0;
+                self.select
+                    .insert_before(&placeholder, self.select.first_child().as_ref())
+                    .unwrap_throw();
+                if !had_selection {
+                    placeholder.set_selected(true);
+                }
+                inner.placeholder = Some(placeholder);
+            }
+            (None, Some(placeholder)) => {
+                placeholder.remove();
+                inner.placeholder = None;
+            }
+            (None, None) => {}
+        }
+    }
+    /// Enable or disable the given option. Does nothing if there is no such option.
+    pub fn set_option_disabled<Q>(&self, key: &Q, disabled: bool)
+    where
+        O: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if let Some(elem) = self.inner.borrow().map.get(key) {
+            elem.set_disabled(disabled);
+        }
+    }
+    /// Let the user select several options (a `<select multiple>`).
+    ///
+    /// Use [values][Self::values] or [value_set][Self::value_set] to get them.
+    pub fn set_multiple(&self, multiple: bool) {
+        self.select.set_multiple(multiple);
+        if let Some(placeholder) = &self.inner.borrow().placeholder {
+            placeholder.set_hidden(multiple);
+            if multiple {
+                placeholder.set_selected(false);
+            }
         }
     }
     pub async fn render(&self) {
@@ -178,15 +355,44 @@ impl<O: Eq + Hash + Clone> Dropdown<O> {
     ) -> impl Future<Output = web_sys::Event> + Stream<Item = web_sys::Event> + '_ {
         self.select.until_change()
     }
+    /// Get a [Stream] that yields the selected option every time the selection
+    /// is changed by the user.
+    pub fn selected_stream(&self) -> impl Stream<Item = Option<O>> + Unpin + '_ {
+        self.until_change().map(|_| self.value())
+    }
+    /// Get a [Stream] that yields the selected options every time the selection
+    /// is changed by the user (for [multiple][Self::set_multiple] mode).
+    pub fn selected_values_stream(&self) -> impl Stream<Item = Vec<O>> + Unpin + '_ {
+        self.until_change().map(|_| self.values())
+    }
     /// Get the currently selected option.
     ///
     /// Returns `None` if nothing is currently selected.
+    /// In [multiple][Self::set_multiple] mode, this is the first selected option.
     pub fn value(&self) -> Option<O> {
-        let si = self.select.selected_index();
         let inner = self.inner.borrow();
-        (si >= 0)
-            .then(|| inner.prev_opts_list.get(si as usize).cloned())
-            .flatten()
-            .or_else(|| inner.selected.clone())
+        if self.select.length() == 0 {
+            // No options in the DOM yet; fall back to what we last knew.
+            return inner.selected.clone();
+        }
+        inner
+            .prev_opts_list
+            .iter()
+            .find(|opt| inner.map[*opt].selected())
+            .cloned()
+    }
+    /// Get all the selected options, in the order they are displayed.
+    pub fn values(&self) -> Vec<O> {
+        let inner = self.inner.borrow();
+        inner
+            .prev_opts_list
+            .iter()
+            .filter(|opt| inner.map[*opt].selected())
+            .cloned()
+            .collect()
+    }
+    /// Get all the selected options, as a set.
+    pub fn value_set(&self) -> HashSet<O> {
+        self.values().into_iter().collect()
     }
 }
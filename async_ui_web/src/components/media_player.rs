@@ -0,0 +1,178 @@
+use std::{future::Future, ops::Deref, time::Duration};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::{combinators::UiFutureExt, ContainerNodeFuture};
+use async_ui_web_html::{events::EmitEvent, media::MediaControl, nodes::Video};
+use futures_lite::{stream, StreamExt};
+use web_sys::{EventTarget, HtmlMediaElement, Node};
+
+/// Events after which the [PlaybackState] may have changed.
+const STATE_EVENTS: [&str; 11] = [
+    "play",
+    "pause",
+    "playing",
+    "waiting",
+    "ended",
+    "timeupdate",
+    "durationchange",
+    "loadedmetadata",
+    "emptied",
+    "seeked",
+    "volumechange",
+];
+
+/// What a [MediaPlayer] is doing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PlaybackStatus {
+    /// Paused, or not started yet.
+    #[default]
+    Paused,
+    Playing,
+    /// Trying to play, but waiting for more data.
+    Buffering,
+    /// Reached the end.
+    Ended,
+}
+
+impl PlaybackStatus {
+    fn of(element: &HtmlMediaElement) -> Self {
+        if element.ended() {
+            Self::Ended
+        } else if element.paused() {
+            Self::Paused
+        } else if element.ready_state() < HtmlMediaElement::HAVE_FUTURE_DATA {
+            Self::Buffering
+        } else {
+            Self::Playing
+        }
+    }
+}
+
+/// A snapshot of a [MediaPlayer]'s state.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PlaybackState {
+    pub status: PlaybackStatus,
+    pub position: Duration,
+    /// `None` until the metadata has loaded, and for unbounded streams.
+    pub duration: Option<Duration>,
+    /// From 0.0 to 1.0.
+    pub volume: f64,
+    pub muted: bool,
+}
+
+/**
+An `<audio>` or `<video>` element whose playback state is kept in a [ReactiveCell].
+
+```
+# use async_ui_web::{components::{MediaPlayer, PlaybackStatus}, html::{Audio, Button}, join, prelude_traits::*, NoChild};
+# let _ = async {
+let player = MediaPlayer::<Audio>::new();
+player.set_src("song.ogg");
+let button = Button::new();
+join((
+    player.render(NoChild),
+    button.render("Play/Pause".render()),
+    async {
+        loop {
+            button.until_click().await;
+            if player.state().borrow().status == PlaybackStatus::Playing {
+                player.pause().ok();
+            } else {
+                player.play_async().await.ok();
+            }
+        }
+    },
+))
+.await;
+# };
+```
+
+The state is updated from the element's events while the player is rendered.
+Use [ReactiveCell::until_change] or [ReactiveCell::watch] on [state][Self::state]
+to react to it.
+
+This type [Deref]s to the node (by default [Video]), so all the HTML methods
+and [MediaControl] are available on it.
+ */
+pub struct MediaPlayer<N = Video> {
+    node: N,
+    state: ReactiveCell<PlaybackState>,
+}
+
+impl<N: Default + AsRef<HtmlMediaElement> + AsRef<Node>> Default for MediaPlayer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> Deref for MediaPlayer<N> {
+    type Target = N;
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl<N: AsRef<HtmlMediaElement> + AsRef<Node>> MediaPlayer<N> {
+    /// Create a new player with a new element.
+    pub fn new() -> Self
+    where
+        N: Default,
+    {
+        Self::from_node(N::default())
+    }
+    /// Create a player around an existing [Video] or [Audio][async_ui_web_html::nodes::Audio].
+    pub fn from_node(node: N) -> Self {
+        let player = Self {
+            node,
+            state: ReactiveCell::new(PlaybackState::default()),
+        };
+        player.update_state();
+        player
+    }
+    /// The playback state.
+    pub fn state(&self) -> &ReactiveCell<PlaybackState> {
+        &self.state
+    }
+    fn element(&self) -> &HtmlMediaElement {
+        self.node.as_ref()
+    }
+    fn update_state(&self) {
+        let element = self.element();
+        let new = PlaybackState {
+            status: PlaybackStatus::of(element),
+            position: element.position(),
+            duration: element.media_duration(),
+            volume: element.volume(),
+            muted: element.muted(),
+        };
+        // only notify on real changes
+        if *self.state.borrow() != new {
+            *self.state.borrow_mut() = new;
+        }
+    }
+    /// Put the element on the screen, and keep [state][Self::state] up to date.
+    ///
+    /// The given future renders inside the element (for `<source>` or `<track>` children).
+    /// The returned future completes when it completes.
+    ///
+    /// This method should only be called once. It may misbehave otherwise.
+    pub async fn render<F: Future>(&self, c: F) -> F::Output {
+        let node: &Node = self.node.as_ref();
+        ContainerNodeFuture::new(c, node.clone())
+            .meanwhile(self.track())
+            .await
+    }
+    async fn track(&self) {
+        let target: &EventTarget = self.element().as_ref();
+        let mut events = STATE_EVENTS
+            .iter()
+            .fold(stream::empty().boxed_local(), |all, name| {
+                all.or(target.until_event::<web_sys::Event>((*name).into()))
+                    .boxed_local()
+            });
+        loop {
+            self.update_state();
+            events.next().await;
+        }
+    }
+}
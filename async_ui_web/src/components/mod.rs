@@ -2,8 +2,21 @@
 //!
 //! Components in this module are provided for convenience.
 
+mod combobox;
+mod dialog;
 mod dropdown;
 mod dynamic_slot;
+mod media_player;
+mod raw_html;
+mod tabs;
+mod tree_view;
 
+pub use crate::selection::SelectionMode;
+pub use combobox::Combobox;
+pub use dialog::{confirm_dialog, Dialog};
 pub use dropdown::Dropdown;
 pub use dynamic_slot::DynamicSlot;
+pub use media_player::{MediaPlayer, PlaybackState, PlaybackStatus};
+pub use raw_html::{RawHtml, Sanitizer};
+pub use tabs::Tabs;
+pub use tree_view::TreeView;
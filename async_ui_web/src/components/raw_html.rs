@@ -0,0 +1,348 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    future::pending,
+};
+
+use async_ui_web_core::{combinators::join, window::DOCUMENT, ContainerNodeFuture};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Element, HtmlTemplateElement, Node};
+
+/**
+Render a string of HTML, after sanitizing it.
+
+Use this to display HTML from places you don't fully control, such as
+content from a CMS. The HTML is parsed inertly (scripts don't run and
+resources don't load while parsing), then everything not permitted by
+the [Sanitizer] is removed.
+
+```
+# use async_ui_web::components::{RawHtml, Sanitizer};
+# let _ = async {
+// with the default allowlist
+let content = RawHtml::new("<p>Hello <b>world</b><script>alert(1)</script></p>");
+content.render().await;
+
+// with a custom allowlist
+let sanitizer = Sanitizer::new().allow_tags(["p", "em"]);
+let content = RawHtml::with_sanitizer("<p><em>only</em> these</p>", &sanitizer);
+content.render().await;
+# };
+```
+
+The resulting nodes are rendered like any other child: they appear where
+[render][Self::render] is awaited, and are removed when that future is dropped.
+*/
+pub struct RawHtml {
+    nodes: Vec<Node>,
+}
+
+impl RawHtml {
+    /// Parse and sanitize the given HTML with [Sanitizer::default].
+    pub fn new(html: &str) -> Self {
+        Self::with_sanitizer(html, &Sanitizer::default())
+    }
+    /// Parse and sanitize the given HTML with the given [Sanitizer].
+    pub fn with_sanitizer(html: &str, sanitizer: &Sanitizer) -> Self {
+        let template: HtmlTemplateElement = DOCUMENT
+            .with(|doc| doc.create_element("template"))
+            .unwrap_throw()
+            .unchecked_into();
+        template.set_inner_html(html);
+        let content: Node = template.content().into();
+        sanitizer.clean_children(&content);
+        let mut nodes = Vec::new();
+        while let Some(child) = content.first_child() {
+            content.remove_child(&child).unwrap_throw();
+            nodes.push(child);
+        }
+        Self { nodes }
+    }
+    /// Put the sanitized nodes on the screen.
+    ///
+    /// This async method never completes.
+    ///
+    /// This method should only be called once. It may misbehave otherwise.
+    pub async fn render(&self) {
+        join(
+            self.nodes
+                .iter()
+                .map(|node| ContainerNodeFuture::new(pending::<()>(), node.clone()))
+                .collect::<Vec<_>>(),
+        )
+        .await;
+    }
+}
+
+/// An allowlist of the tags, attributes, and URL schemes [RawHtml] keeps.
+///
+/// Anything not in the allowlist is removed:
+/// *   Elements with disallowed tags are replaced by their (sanitized) children.
+///     The exception is elements whose content isn't meant to be displayed as
+///     text (such as `<script>`, `<style>`, or `<iframe>`); those are removed
+///     along with everything inside.
+/// *   Disallowed attributes are removed.
+///     Event handler attributes (`on*`) are always removed, even if allowed.
+/// *   URL attributes (such as `href` and `src`) are removed if their value
+///     uses a scheme not in the allowlist (for example `javascript:`).
+///     Relative URLs are always kept.
+/// *   Comments are removed.
+///
+/// [Sanitizer::default] allows common text formatting, lists, tables, links,
+/// and images. [Sanitizer::new] allows nothing but text.
+#[derive(Clone, Debug)]
+pub struct Sanitizer {
+    tags: HashSet<Cow<'static, str>>,
+    attributes: HashSet<Cow<'static, str>>,
+    tag_attributes: HashMap<Cow<'static, str>, HashSet<Cow<'static, str>>>,
+    url_schemes: HashSet<Cow<'static, str>>,
+}
+
+/// Elements that are removed with all their content when not allowed.
+const DROP_WITH_CONTENT: &[&str] = &[
+    "script", "style", "template", "iframe", "object", "embed", "noscript", "noembed", "noframes",
+    "textarea", "title", "select", "svg", "math",
+];
+
+/// Attributes whose values are URLs.
+const URL_ATTRIBUTES: &[&str] = &[
+    "href",
+    "src",
+    "cite",
+    "action",
+    "formaction",
+    "poster",
+    "background",
+    "xlink:href",
+];
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self::new()
+            .allow_tags([
+                "a",
+                "abbr",
+                "b",
+                "blockquote",
+                "br",
+                "caption",
+                "code",
+                "dd",
+                "del",
+                "div",
+                "dl",
+                "dt",
+                "em",
+                "figcaption",
+                "figure",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "ins",
+                "kbd",
+                "li",
+                "mark",
+                "ol",
+                "p",
+                "pre",
+                "q",
+                "s",
+                "small",
+                "span",
+                "strong",
+                "sub",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "tr",
+                "u",
+                "ul",
+            ])
+            .allow_attributes(["class", "dir", "lang", "title"])
+            .allow_tag_attributes("a", ["href"])
+            .allow_tag_attributes("img", ["src", "alt", "width", "height"])
+            .allow_tag_attributes("td", ["colspan", "rowspan"])
+            .allow_tag_attributes("th", ["colspan", "rowspan", "scope"])
+            .allow_tag_attributes("ol", ["start", "reversed"])
+            .allow_tag_attributes("blockquote", ["cite"])
+            .allow_tag_attributes("q", ["cite"])
+            .allow_url_schemes(["http", "https", "mailto"])
+    }
+}
+
+impl Sanitizer {
+    /// Create a sanitizer that allows nothing but text.
+    ///
+    /// Use the `allow_*` methods to build up the allowlist.
+    pub fn new() -> Self {
+        Self {
+            tags: HashSet::new(),
+            attributes: HashSet::new(),
+            tag_attributes: HashMap::new(),
+            url_schemes: HashSet::new(),
+        }
+    }
+    /// Allow elements with these tag names (e.g. `"p"`).
+    pub fn allow_tags<T: Into<Cow<'static, str>>>(
+        mut self,
+        tags: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.tags
+            .extend(tags.into_iter().map(|t| lowercase(t.into())));
+        self
+    }
+    /// Allow these attributes on every allowed element.
+    pub fn allow_attributes<T: Into<Cow<'static, str>>>(
+        mut self,
+        attributes: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.attributes
+            .extend(attributes.into_iter().map(|a| lowercase(a.into())));
+        self
+    }
+    /// Allow these attributes on elements with the given tag name.
+    ///
+    /// This does not allow the tag itself; use [allow_tags][Self::allow_tags] for that.
+    pub fn allow_tag_attributes<T: Into<Cow<'static, str>>>(
+        mut self,
+        tag: impl Into<Cow<'static, str>>,
+        attributes: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.tag_attributes
+            .entry(lowercase(tag.into()))
+            .or_default()
+            .extend(attributes.into_iter().map(|a| lowercase(a.into())));
+        self
+    }
+    /// Allow URL attributes to use these schemes (e.g. `"https"`).
+    pub fn allow_url_schemes<T: Into<Cow<'static, str>>>(
+        mut self,
+        schemes: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.url_schemes
+            .extend(schemes.into_iter().map(|s| lowercase(s.into())));
+        self
+    }
+
+    fn clean_children(&self, parent: &Node) {
+        let mut next_child = parent.first_child();
+        while let Some(child) = next_child {
+            next_child = child.next_sibling();
+            match child.node_type() {
+                Node::TEXT_NODE => {}
+                Node::ELEMENT_NODE => self.clean_element(parent, child.unchecked_into()),
+                _ => {
+                    parent.remove_child(&child).unwrap_throw();
+                }
+            }
+        }
+    }
+
+    fn clean_element(&self, parent: &Node, element: Element) {
+        let tag = element.local_name().to_ascii_lowercase();
+        if self.tags.contains(tag.as_str()) {
+            self.clean_attributes(&tag, &element);
+            self.clean_children(&element);
+        } else if DROP_WITH_CONTENT.contains(&tag.as_str()) {
+            parent.remove_child(&element).unwrap_throw();
+        } else {
+            // unwrap: clean the children, then put them in place of the element
+            self.clean_children(&element);
+            while let Some(grandchild) = element.first_child() {
+                parent
+                    .insert_before(&grandchild, Some(&element))
+                    .unwrap_throw();
+            }
+            parent.remove_child(&element).unwrap_throw();
+        }
+    }
+
+    fn clean_attributes(&self, tag: &str, element: &Element) {
+        for name in element.get_attribute_names().iter() {
+            let Some(name) = name.as_string() else {
+                continue;
+            };
+            let lower = name.to_ascii_lowercase();
+            let allowed = !lower.starts_with("on")
+                && (self.attributes.contains(lower.as_str())
+                    || self
+                        .tag_attributes
+                        .get(tag)
+                        .is_some_and(|attrs| attrs.contains(lower.as_str())))
+                && (!URL_ATTRIBUTES.contains(&lower.as_str())
+                    || element
+                        .get_attribute(&name)
+                        .is_some_and(|value| is_allowed_url(&value, &self.url_schemes)));
+            if !allowed {
+                element.remove_attribute(&name).unwrap_throw();
+            }
+        }
+    }
+}
+
+fn lowercase(s: Cow<'static, str>) -> Cow<'static, str> {
+    if s.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(s.to_ascii_lowercase())
+    } else {
+        s
+    }
+}
+
+/// Check that the URL is relative or uses one of the allowed schemes.
+fn is_allowed_url(url: &str, schemes: &HashSet<Cow<'static, str>>) -> bool {
+    // Browsers ignore whitespace and control characters in the scheme
+    // (so `java\tscript:` is `javascript:`), so we do too.
+    let url = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match url.find([':', '/', '?', '#']) {
+        Some(idx) if url[idx..].starts_with(':') => schemes.contains(&url[..idx]),
+        // no scheme: a relative URL
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schemes() -> HashSet<Cow<'static, str>> {
+        ["http", "https", "mailto"].map(Cow::Borrowed).into()
+    }
+
+    #[test]
+    fn allowed_schemes() {
+        assert!(is_allowed_url("https://example.com/", &schemes()));
+        assert!(is_allowed_url("HTTP://example.com/", &schemes()));
+        assert!(is_allowed_url("mailto:someone@example.com", &schemes()));
+    }
+
+    #[test]
+    fn relative_urls() {
+        assert!(is_allowed_url("/path/to/page", &schemes()));
+        assert!(is_allowed_url("page?next=javascript:alert(1)", &schemes()));
+        assert!(is_allowed_url("#section", &schemes()));
+        assert!(is_allowed_url("", &schemes()));
+    }
+
+    #[test]
+    fn disallowed_schemes() {
+        assert!(!is_allowed_url("javascript:alert(1)", &schemes()));
+        assert!(!is_allowed_url(" JavaScript:alert(1)", &schemes()));
+        assert!(!is_allowed_url("java\tscript:alert(1)", &schemes()));
+        assert!(!is_allowed_url("java\u{0}script:alert(1)", &schemes()));
+        assert!(!is_allowed_url("data:text/html,<script>", &schemes()));
+    }
+}
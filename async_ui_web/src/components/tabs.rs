@@ -0,0 +1,278 @@
+use std::{cell::Cell, collections::HashMap, future::Future, hash::Hash, pin::Pin};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::combinators::{join, race};
+use async_ui_web_html::{
+    events::EmitElementEvent,
+    nodes::{Button, Div},
+};
+use futures_lite::{Stream, StreamExt};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlElement, Node};
+
+use crate::{
+    focus::{Orientation, RovingTabindex},
+    lists::DynamicList,
+    unique_id::unique_id,
+    NoChild,
+};
+
+/**
+Tabs, with a panel for the selected one.
+
+Like [Dropdown][super::Dropdown], tabs are identified by values of an
+`Eq + Hash + Clone` Rust type.
+
+```
+# use async_ui_web::{components::Tabs, prelude_traits::*};
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Section {
+    General,
+    Advanced,
+}
+
+# let _ = async {
+let tabs = Tabs::new();
+tabs.update_tabs([(Section::General, "General"), (Section::Advanced, "Advanced")]);
+tabs.render(|section| async move {
+    match section {
+        Section::General => "general settings".render().await,
+        Section::Advanced => "advanced settings".render().await,
+    }
+})
+.await;
+# };
+```
+
+The tabs are rendered as buttons in a `<div role="tablist">`, followed by the
+panels, each in a `<div role="tabpanel">`, with the ARIA attributes linking them.
+The arrow keys (and Home and End) move between the tabs, selecting them as they go.
+
+By default, only the selected tab's panel is rendered, so switching away from
+a tab drops its future.
+With [set_keep_alive][Self::set_keep_alive], panels stay alive (just hidden) once opened.
+ */
+pub struct Tabs<K: Eq + Hash + Clone> {
+    tablist: Div,
+    inner: ReactiveCell<Inner<K>>,
+    selected: ReactiveCell<Option<K>>,
+    roving: RovingTabindex,
+    keep_alive: Cell<bool>,
+    id_prefix: String,
+}
+
+struct Inner<K> {
+    order: Vec<K>,
+    tabs: HashMap<K, Tab>,
+    next_serial: u64,
+}
+
+struct Tab {
+    button: Button,
+    panel_id: String,
+}
+
+type PanelFuture<'c> = Pin<Box<dyn Future<Output = ()> + 'c>>;
+
+impl<K: Eq + Hash + Clone> Default for Tabs<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone> Tabs<K> {
+    /// Create a new component with no tabs.
+    ///
+    /// Use [update_tabs][Self::update_tabs] to set the tabs to show.
+    pub fn new() -> Self {
+        let tablist = Div::new();
+        tablist.set_attribute("role", "tablist").unwrap_throw();
+        let roving = RovingTabindex::new();
+        roving.set_orientation(Orientation::Horizontal);
+        Self {
+            tablist,
+            inner: ReactiveCell::new(Inner {
+                order: Vec::new(),
+                tabs: HashMap::new(),
+                next_serial: 0,
+            }),
+            selected: ReactiveCell::new(None),
+            roving,
+            keep_alive: Cell::new(false),
+            id_prefix: unique_id("tabs"),
+        }
+    }
+    /// Set the tabs to show, in order: the value identifying each tab, and its label.
+    ///
+    /// If the selected tab is removed, the first tab is selected.
+    pub fn update_tabs<'t>(&self, tabs: impl IntoIterator<Item = (K, &'t str)>) {
+        let mut inner = self.inner.borrow_mut();
+        let Inner {
+            order,
+            tabs: map,
+            next_serial,
+        } = &mut *inner;
+        let mut new_order = Vec::new();
+        for (key, label) in tabs {
+            let tab = map.entry(key.clone()).or_insert_with(|| {
+                let id = format!("{}-{}", self.id_prefix, next_serial);
+                *next_serial += 1;
+                let button = Button::new();
+                button.set_type("button");
+                button.set_id(&id);
+                button.set_attribute("role", "tab").unwrap_throw();
+                let panel_id = format!("{id}-panel");
+                button
+                    .set_attribute("aria-controls", &panel_id)
+                    .unwrap_throw();
+                Tab { button, panel_id }
+            });
+            tab.button.set_text_content(Some(label));
+            // appending an existing child moves it, so this puts the buttons in order
+            self.tablist.append_child(&tab.button).unwrap_throw();
+            new_order.push(key);
+        }
+        map.retain(|key, tab| {
+            let keep = new_order.contains(key);
+            if !keep {
+                tab.button.remove();
+            }
+            keep
+        });
+        *order = new_order;
+        self.roving.set_items(
+            order
+                .iter()
+                .map(|key| AsRef::<HtmlElement>::as_ref(&map[key].button).clone()),
+        );
+        let first = order.first().cloned();
+        let selected_exists = self
+            .selected
+            .borrow()
+            .as_ref()
+            .is_some_and(|key| map.contains_key(key));
+        drop(inner);
+        if !selected_exists {
+            *self.selected.borrow_mut() = first;
+        }
+    }
+    /// Whether to keep the panels of tabs that aren't selected alive (but hidden),
+    /// once they've been opened. Defaults to `false`.
+    pub fn set_keep_alive(&self, keep_alive: bool) {
+        self.keep_alive.set(keep_alive);
+        // wake the render loop to add or drop panels
+        drop(self.inner.borrow_mut());
+    }
+    /// Get the selected tab. `None` only if there are no tabs.
+    pub fn value(&self) -> Option<K> {
+        self.selected.borrow().clone()
+    }
+    /// Select the given tab. Does nothing if there is no such tab.
+    pub fn set_value(&self, key: K) {
+        if self.inner.borrow().tabs.contains_key(&key) && self.value().as_ref() != Some(&key) {
+            *self.selected.borrow_mut() = Some(key);
+        }
+    }
+    /// Get a [Stream] that fires every time the selected tab changes,
+    /// whether by the user or by [set_value][Self::set_value].
+    pub fn until_change(&self) -> impl Future<Output = ()> + Stream<Item = ()> + '_ {
+        self.selected.until_change()
+    }
+    /// Put the tabs on the screen.
+    ///
+    /// The given function is called with a tab's value to get the future to render
+    /// in its panel, whenever a panel is opened.
+    ///
+    /// This async method never completes.
+    pub async fn render<F: Future<Output = ()>>(&self, panel: impl Fn(K) -> F) {
+        let panels = DynamicList::<K, PanelFuture<'_>>::new();
+        join((
+            self.tablist.render(NoChild),
+            panels.render(),
+            self.roving.run(&self.tablist),
+            self.handle_input(),
+            self.update_panels(&panels, &panel),
+        ))
+        .await;
+    }
+    async fn update_panels<'c, F: Future<Output = ()> + 'c>(
+        &'c self,
+        panels: &DynamicList<'c, K, PanelFuture<'c>>,
+        panel: &'c impl Fn(K) -> F,
+    ) {
+        let mut panel_nodes = HashMap::new();
+        loop {
+            self.sync_panels(panels, panel, &mut panel_nodes);
+            race((self.selected.until_change(), self.inner.until_change())).await;
+        }
+    }
+    /// Open, hide, or drop panels, and mark the selected tab, to match the selection.
+    fn sync_panels<'c, F: Future<Output = ()> + 'c>(
+        &'c self,
+        panels: &DynamicList<'c, K, PanelFuture<'c>>,
+        panel: &'c impl Fn(K) -> F,
+        panel_nodes: &mut HashMap<K, HtmlElement>,
+    ) {
+        let selected = self.value();
+        let inner = self.inner.borrow();
+        let keep = |key: &K| {
+            inner.tabs.contains_key(key)
+                && (self.keep_alive.get() || Some(key) == selected.as_ref())
+        };
+        panels.retain(keep);
+        panel_nodes.retain(|key, _| keep(key));
+        if let Some(key) = selected.as_ref().filter(|key| !panels.contains_key(*key)) {
+            let tab = &inner.tabs[key];
+            let node = Div::new();
+            node.set_id(&tab.panel_id);
+            node.set_attribute("role", "tabpanel").unwrap_throw();
+            node.set_attribute("aria-labelledby", &tab.button.id())
+                .unwrap_throw();
+            node.set_tab_index(0);
+            panel_nodes.insert(key.clone(), node.element.clone().into());
+            let content = panel(key.clone());
+            panels.insert(
+                key.clone(),
+                Box::pin(async move { node.render(content).await }),
+                None,
+            );
+        }
+        for (key, node) in panel_nodes.iter() {
+            node.set_hidden(Some(key) != selected.as_ref());
+        }
+        for (index, key) in inner.order.iter().enumerate() {
+            let is_selected = Some(key) == selected.as_ref();
+            inner.tabs[key]
+                .button
+                .set_attribute("aria-selected", if is_selected { "true" } else { "false" })
+                .unwrap_throw();
+            if is_selected {
+                self.roving.set_active(index);
+            }
+        }
+    }
+    /// Select tabs when they're clicked, or focused with the arrow keys.
+    async fn handle_input(&self) {
+        let mut targets = self
+            .tablist
+            .until_click()
+            .map(|ev| ev.target())
+            .or(self.tablist.until_focusin().map(|ev| ev.target()));
+        while let Some(target) = targets.next().await {
+            let Some(node) = target.and_then(|t| t.dyn_into::<Node>().ok()) else {
+                continue;
+            };
+            let key = {
+                let inner = self.inner.borrow();
+                inner
+                    .order
+                    .iter()
+                    .find(|key| inner.tabs[*key].button.contains(Some(&node)))
+                    .cloned()
+            };
+            if let Some(key) = key {
+                self.set_value(key);
+            }
+        }
+    }
+}
@@ -0,0 +1,730 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    future::Future,
+    hash::Hash,
+    ops::Deref,
+    pin::Pin,
+};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::combinators::{join, race, UiFutureExt};
+use async_ui_web_html::{
+    events::{EmitElementEvent, OverflowPolicy},
+    nodes::{Div, Li, Span, Ul},
+};
+use futures_lite::{Stream, StreamExt};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{EventTarget, HtmlElement, KeyboardEvent, Node};
+
+use crate::{
+    focus::{Orientation, RovingTabindex},
+    lists::{ListModel, ModeledList},
+    selection::{click_select, ClickModifiers, SelectionMode},
+    shortcuts::{ShortcutClassList, ShortcutRenderStr},
+    NoChild,
+};
+
+type NodeFuture<'c> = Pin<Box<dyn Future<Output = ()> + 'c>>;
+type LoadFuture<'c, K> = Pin<Box<dyn Future<Output = Vec<K>> + 'c>>;
+
+/**
+A tree of nodes that expand and collapse, loading their children when first expanded.
+
+Like [Dropdown][super::Dropdown], nodes are identified by values of an
+`Eq + Hash + Clone` Rust type. Each node must appear only once in the whole tree.
+
+```
+# use async_ui_web::{components::{SelectionMode, TreeView}, prelude_traits::*};
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Entry {
+    path: String,
+    is_dir: bool,
+}
+# async fn read_dir(path: &str) -> Vec<Entry> { Vec::new() }
+
+# let _ = async {
+let tree = TreeView::new();
+tree.set_attribute("aria-label", "Files").unwrap();
+tree.set_roots([Entry {
+    path: "/".into(),
+    is_dir: true,
+}]);
+tree.set_selection_mode(SelectionMode::Single);
+tree.render(
+    |entry| entry.is_dir,
+    |entry| async move { read_dir(&entry.path).await },
+    |entry| entry.path.render(),
+)
+.await;
+# };
+```
+
+The tree is a `<ul role="tree">`. Each node is a `<li role="treeitem">` containing
+a `<div class="tree-view-item">` (with a `<span class="tree-view-toggle">`, then the label),
+followed by its children in a `<ul role="group">` when expanded.
+While the children load, a `<div class="tree-view-loading">` is shown in their place.
+Selected nodes have the `selected` class.
+
+Clicking the toggle expands or collapses a node, and clicking elsewhere on the item selects it.
+The keyboard works as in the [WAI-ARIA tree pattern](https://www.w3.org/WAI/ARIA/apg/patterns/treeview/):
+Up and Down (and Home and End) move between the nodes on screen, Right expands a node
+or moves to its first child, Left collapses a node or moves to its parent,
+`*` expands a node's siblings, and Space or Enter selects.
+With [SelectionMode::Multiple], Shift+Up and Shift+Down extend the selection,
+and Ctrl+A selects every node on screen.
+
+Which nodes are expanded can be read and changed with [set_expanded][Self::set_expanded]
+and the related methods, whether or not the tree is on screen.
+Children lists are [ModeledList]s, so inserting or removing a child
+(see [insert_child][Self::insert_child]) doesn't render its siblings again.
+
+This type [Deref]s to [Ul], the tree element.
+ */
+pub struct TreeView<K: Eq + Hash + Clone> {
+    tree: Ul,
+    roving: RovingTabindex,
+    inner: ReactiveCell<Inner<K>>,
+    expanded: ReactiveCell<HashSet<K>>,
+    selection: ReactiveCell<HashSet<K>>,
+    selection_mode: Cell<SelectionMode>,
+    /// The node that Shift-clicks select from.
+    anchor: RefCell<Option<K>>,
+    /// The `<li>` of every node on screen.
+    elements: ReactiveCell<HashMap<K, HtmlElement>>,
+    /// The nodes on screen, in order. These are the items of `roving`.
+    focus_order: RefCell<Vec<K>>,
+}
+
+struct Inner<K> {
+    roots: ListModel<K>,
+    nodes: HashMap<K, NodeState<K>>,
+}
+
+struct NodeState<K> {
+    parent: Option<K>,
+    /// `None` until loaded.
+    children: Option<ListModel<K>>,
+}
+
+/// The functions given to [TreeView::render], type-erased to be passed down the tree.
+struct Callbacks<'c, K> {
+    has_children: &'c dyn Fn(&K) -> bool,
+    load: &'c dyn Fn(K) -> LoadFuture<'c, K>,
+    label: &'c dyn Fn(&K) -> NodeFuture<'c>,
+}
+
+impl<K: Eq + Hash + Clone> Default for TreeView<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone> Deref for TreeView<K> {
+    type Target = Ul;
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+impl<K: Eq + Hash + Clone> TreeView<K> {
+    /// Create an empty tree.
+    ///
+    /// Use [set_roots][Self::set_roots] to set the top-level nodes.
+    pub fn new() -> Self {
+        let tree = Ul::new();
+        tree.set_attribute("role", "tree").unwrap_throw();
+        tree.add_class("tree-view");
+        let roving = RovingTabindex::new();
+        roving.set_orientation(Orientation::Vertical);
+        roving.set_wrap(false);
+        Self {
+            tree,
+            roving,
+            inner: ReactiveCell::new(Inner {
+                roots: ListModel::new(),
+                nodes: HashMap::new(),
+            }),
+            expanded: ReactiveCell::new(HashSet::new()),
+            selection: ReactiveCell::new(HashSet::new()),
+            selection_mode: Cell::new(SelectionMode::None),
+            anchor: RefCell::new(None),
+            elements: ReactiveCell::new(HashMap::new()),
+            focus_order: RefCell::new(Vec::new()),
+        }
+    }
+    /// Set the top-level nodes, in order.
+    pub fn set_roots(&self, roots: impl IntoIterator<Item = K>) {
+        let roots: Vec<K> = roots.into_iter().collect();
+        let mut inner = self.inner.borrow_mut();
+        for root in &roots {
+            inner.node_mut(root).parent = None;
+        }
+        // the list keeps the nodes that are still there, even with a new model
+        inner.roots = ListModel::from(roots);
+    }
+    /// Set the children of a node, as if they had been loaded.
+    pub fn set_children(&self, parent: &K, children: impl IntoIterator<Item = K>) {
+        let children: Vec<K> = children.into_iter().collect();
+        self.inner.borrow_mut().set_children(parent, children);
+    }
+    /// Insert a child at the given index among the children of `parent`.
+    ///
+    /// Does nothing if the parent's children haven't been loaded yet
+    /// (the child will be there if the loading function returns it).
+    pub fn insert_child(&self, parent: &K, index: usize, child: K) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.children(parent).is_none() {
+            return;
+        }
+        inner.node_mut(&child).parent = Some(parent.clone());
+        inner
+            .node_mut(parent)
+            .children
+            .as_mut()
+            .unwrap_throw()
+            .insert(index, child);
+    }
+    /// Remove a node (and so, its descendants) from the tree.
+    ///
+    /// The removed nodes are also collapsed and deselected.
+    pub fn remove_node(&self, node: &K) {
+        let removed = self.inner.borrow_mut().remove_subtree(node);
+        if removed.is_empty() {
+            return;
+        }
+        if removed.iter().any(|n| self.expanded.borrow().contains(n)) {
+            let mut expanded = self.expanded.borrow_mut();
+            removed.iter().for_each(|n| {
+                expanded.remove(n);
+            });
+        }
+        if removed.iter().any(|n| self.selection.borrow().contains(n)) {
+            let mut selection = self.selection.borrow_mut();
+            removed.iter().for_each(|n| {
+                selection.remove(n);
+            });
+        }
+        let mut anchor = self.anchor.borrow_mut();
+        if anchor.as_ref().is_some_and(|a| removed.contains(a)) {
+            *anchor = None;
+        }
+    }
+    /// Forget the children of a node, so they're loaded again.
+    /// If the node is expanded, that happens right away.
+    pub fn reload(&self, node: &K) {
+        if self.inner.borrow().children(node).is_some() {
+            self.inner.borrow_mut().node_mut(node).children = None;
+        }
+    }
+    /// Get the parent of a node. `None` for top-level nodes, and nodes not in the tree.
+    pub fn parent(&self, node: &K) -> Option<K> {
+        self.inner.borrow().nodes.get(node)?.parent.clone()
+    }
+    /// Whether the given node is expanded.
+    pub fn is_expanded(&self, node: &K) -> bool {
+        self.expanded.borrow().contains(node)
+    }
+    /// Expand or collapse the given node.
+    ///
+    /// This works even for nodes that aren't loaded or on screen yet;
+    /// they will show up expanded.
+    pub fn set_expanded(&self, node: &K, expanded: bool) {
+        if self.is_expanded(node) != expanded {
+            let mut set = self.expanded.borrow_mut();
+            if expanded {
+                set.insert(node.clone());
+            } else {
+                set.remove(node);
+            }
+        }
+    }
+    /// Get all the expanded nodes.
+    pub fn expanded_nodes(&self) -> HashSet<K> {
+        self.expanded.borrow().clone()
+    }
+    /// Expand exactly the given nodes, collapsing all others.
+    pub fn set_expanded_nodes(&self, nodes: impl IntoIterator<Item = K>) {
+        *self.expanded.borrow_mut() = nodes.into_iter().collect();
+    }
+    /// Get a [Stream] that fires every time a node is expanded or collapsed,
+    /// whether by the user or by the methods on this tree.
+    pub fn until_expansion_change(&self) -> impl Future<Output = ()> + Stream<Item = ()> + '_ {
+        self.expanded.until_change()
+    }
+    /// Set what clicking a node does. Changing the mode clears the selection.
+    pub fn set_selection_mode(&self, mode: SelectionMode) {
+        if self.selection_mode.replace(mode) == mode {
+            return;
+        }
+        match mode {
+            SelectionMode::None => self.tree.remove_attribute("aria-multiselectable"),
+            SelectionMode::Single => self.tree.set_attribute("aria-multiselectable", "false"),
+            SelectionMode::Multiple => self.tree.set_attribute("aria-multiselectable", "true"),
+        }
+        .unwrap_throw();
+        *self.anchor.borrow_mut() = None;
+        self.selection.borrow_mut().clear();
+    }
+    /// Get the selected nodes.
+    pub fn selected(&self) -> HashSet<K> {
+        self.selection.borrow().clone()
+    }
+    /// Select exactly the given nodes.
+    pub fn set_selected(&self, nodes: impl IntoIterator<Item = K>) {
+        *self.selection.borrow_mut() = nodes.into_iter().collect();
+    }
+    /// Get a [Stream] that fires every time the selection changes,
+    /// whether by the user or by the methods on this tree.
+    pub fn until_selection_change(&self) -> impl Future<Output = ()> + Stream<Item = ()> + '_ {
+        self.selection.until_change()
+    }
+    /// Put the tree on the screen.
+    ///
+    /// *   `has_children` tells whether a node can be expanded.
+    /// *   `load_children` is called with a node the first time it is expanded
+    ///     (and after [reload][Self::reload]), to get its children.
+    /// *   `render_label` is called with each node that comes on screen, to get
+    ///     the future rendering its label.
+    ///
+    /// This async method never completes.
+    pub async fn render<H, L, LF, R, RF>(&self, has_children: H, load_children: L, render_label: R)
+    where
+        H: Fn(&K) -> bool,
+        L: Fn(K) -> LF,
+        LF: Future<Output = Vec<K>>,
+        R: Fn(&K) -> RF,
+        RF: Future<Output = ()>,
+    {
+        let callbacks = Callbacks {
+            has_children: &has_children,
+            load: &|node| Box::pin(load_children(node)) as LoadFuture<'_, K>,
+            label: &|node| Box::pin(render_label(node)) as NodeFuture<'_>,
+        };
+        let roots = ModeledList::new(|node: &K| {
+            Box::pin(self.render_node(node.clone(), 1, &callbacks)) as NodeFuture<'_>
+        });
+        join((
+            self.tree.render(roots.render()),
+            self.keep_list_updated(&roots, None),
+            self.roving.run(&self.tree),
+            self.sync_focus_order(),
+            self.handle_keys(&callbacks),
+        ))
+        .await;
+    }
+    /// Keep the list in sync with the children of `parent` (or the roots, for `None`).
+    async fn keep_list_updated<F: Future, R: Fn(&K) -> F>(
+        &self,
+        list: &ModeledList<'_, K, F, R>,
+        parent: Option<&K>,
+    ) {
+        loop {
+            {
+                let inner = self.inner.borrow();
+                let model = match parent {
+                    Some(parent) => inner.children(parent),
+                    None => Some(&inner.roots),
+                };
+                if let Some(model) = model {
+                    list.update(model);
+                }
+            }
+            self.inner.until_change().await;
+        }
+    }
+    async fn render_node<'c>(&'c self, node: K, level: usize, callbacks: &'c Callbacks<'c, K>) {
+        let li = Li::new();
+        li.set_attribute("role", "treeitem").unwrap_throw();
+        li.set_attribute("aria-level", &level.to_string())
+            .unwrap_throw();
+        li.set_tab_index(-1);
+        let item = Div::new();
+        item.add_class("tree-view-item");
+        let toggle = Span::new();
+        toggle.add_class("tree-view-toggle");
+        toggle.set_attribute("aria-hidden", "true").unwrap_throw();
+        let branch = (callbacks.has_children)(&node);
+
+        self.elements
+            .borrow_mut()
+            .insert(node.clone(), li.element.clone().into());
+        let _registered = scopeguard::guard(node.clone(), |node| {
+            self.elements.borrow_mut().remove(&node);
+        });
+        li.render(join((
+            item.render(join((toggle.render(NoChild), (callbacks.label)(&node)))),
+            async {
+                if branch {
+                    self.render_group(&node, level, callbacks, &li).await;
+                }
+            },
+        )))
+        .meanwhile(join((
+            self.sync_node(&li, &node, branch),
+            self.handle_clicks(&item, &toggle, &node),
+        )))
+        .await;
+    }
+    /// Show the children while the node is expanded.
+    async fn render_group<'c>(
+        &'c self,
+        node: &K,
+        level: usize,
+        callbacks: &'c Callbacks<'c, K>,
+        li: &Li,
+    ) {
+        loop {
+            while !self.is_expanded(node) {
+                self.expanded.until_change().await;
+            }
+            race((self.render_children(node, level, callbacks, li), async {
+                while self.is_expanded(node) {
+                    self.expanded.until_change().await;
+                }
+            }))
+            .await;
+        }
+    }
+    async fn render_children<'c>(
+        &'c self,
+        node: &K,
+        level: usize,
+        callbacks: &'c Callbacks<'c, K>,
+        li: &Li,
+    ) {
+        loop {
+            if !self.is_loaded(node) {
+                li.set_attribute("aria-busy", "true").unwrap_throw();
+                let _busy = scopeguard::guard((), |_| {
+                    li.remove_attribute("aria-busy").unwrap_throw();
+                });
+                let children = (callbacks.load)(node.clone())
+                    .meanwhile(async {
+                        let loading = Div::new();
+                        loading.add_class("tree-view-loading");
+                        loading.render("Loading…".render()).await;
+                    })
+                    .await;
+                self.set_children(node, children);
+            }
+            let group = Ul::new();
+            group.set_attribute("role", "group").unwrap_throw();
+            let list = ModeledList::new(|child: &K| {
+                Box::pin(self.render_node(child.clone(), level + 1, callbacks)) as NodeFuture<'_>
+            });
+            race((
+                async {
+                    join((
+                        group.render(list.render()),
+                        self.keep_list_updated(&list, Some(node)),
+                    ))
+                    .await;
+                },
+                // until reloaded
+                async {
+                    while self.is_loaded(node) {
+                        self.inner.until_change().await;
+                    }
+                },
+            ))
+            .await;
+        }
+    }
+    fn is_loaded(&self, node: &K) -> bool {
+        self.inner.borrow().children(node).is_some()
+    }
+    /// Keep the node's ARIA attributes and classes up to date.
+    async fn sync_node(&self, li: &Li, node: &K, branch: bool) {
+        loop {
+            if branch {
+                let expanded = if self.is_expanded(node) {
+                    "true"
+                } else {
+                    "false"
+                };
+                li.set_attribute("aria-expanded", expanded).unwrap_throw();
+            }
+            let selected = self.selection.borrow().contains(node);
+            li.set_class("selected", selected);
+            if self.selection_mode.get() == SelectionMode::None {
+                li.remove_attribute("aria-selected").unwrap_throw();
+            } else {
+                li.set_attribute("aria-selected", if selected { "true" } else { "false" })
+                    .unwrap_throw();
+            }
+            race((self.expanded.until_change(), self.selection.until_change())).await;
+        }
+    }
+    async fn handle_clicks(&self, item: &Div, toggle: &Span, node: &K) {
+        let toggle: &Node = toggle.as_ref();
+        let mut clicks = item.until_click();
+        while let Some(ev) = clicks.next().await {
+            let on_toggle = ev
+                .target()
+                .and_then(|target| target.dyn_into::<Node>().ok())
+                .is_some_and(|target| toggle.contains(Some(&target)));
+            if on_toggle {
+                self.set_expanded(node, !self.is_expanded(node));
+            } else {
+                self.select(
+                    node,
+                    ClickModifiers {
+                        toggle: ev.ctrl_key() || ev.meta_key(),
+                        shift: ev.shift_key(),
+                    },
+                );
+            }
+        }
+    }
+    fn select(&self, node: &K, modifiers: ClickModifiers) {
+        let mode = self.selection_mode.get();
+        if mode == SelectionMode::None {
+            return;
+        }
+        let mut selection = self.selection.borrow().clone();
+        let mut anchor = self.anchor.borrow().clone();
+        click_select(
+            &mut selection,
+            &mut anchor,
+            &self.focus_order.borrow(),
+            node,
+            mode,
+            modifiers,
+        );
+        *self.anchor.borrow_mut() = anchor;
+        if *self.selection.borrow() != selection {
+            *self.selection.borrow_mut() = selection;
+        }
+    }
+    /// Keep the arrow-key navigation going through the nodes on screen, in order.
+    async fn sync_focus_order(&self) {
+        loop {
+            self.update_focus_order();
+            race((
+                self.inner.until_change(),
+                self.expanded.until_change(),
+                self.elements.until_change(),
+            ))
+            .await;
+        }
+    }
+    fn update_focus_order(&self) {
+        let (nodes, items): (Vec<K>, Vec<HtmlElement>) = {
+            let inner = self.inner.borrow();
+            let expanded = self.expanded.borrow();
+            let elements = self.elements.borrow();
+            visible_nodes(&inner.roots, |node| {
+                inner.children(node).filter(|_| expanded.contains(node))
+            })
+            .into_iter()
+            .filter_map(|node| {
+                let element = elements.get(&node)?.clone();
+                Some((node, element))
+            })
+            .unzip()
+        };
+        let active = self.focus_order.borrow().get(self.roving.active()).cloned();
+        self.roving.set_items(items);
+        // keep the same node active, even if others came or went before it
+        if let Some(index) = active.and_then(|active| nodes.iter().position(|n| *n == active)) {
+            self.roving.set_active(index);
+        }
+        *self.focus_order.borrow_mut() = nodes;
+    }
+    async fn handle_keys(&self, callbacks: &Callbacks<'_, K>) {
+        let mut keydowns = self.tree.until_keydown();
+        keydowns.set_buffer(8, OverflowPolicy::DropOldest);
+        while let Some(ev) = keydowns.next().await {
+            self.handle_key(&ev, callbacks);
+        }
+    }
+    /// The parts of the tree pattern that [RovingTabindex] doesn't do.
+    fn handle_key(&self, ev: &KeyboardEvent, callbacks: &Callbacks<'_, K>) {
+        let order = self.focus_order.borrow().clone();
+        let active = self.roving.active();
+        let Some(current) = order.get(active).cloned() else {
+            return;
+        };
+        let from_item =
+            self.elements.borrow().get(&current).is_some_and(|item| {
+                Some(AsRef::<EventTarget>::as_ref(item)) == ev.target().as_ref()
+            });
+        if !from_item || ev.alt_key() {
+            return;
+        }
+        let toggle = ev.ctrl_key() || ev.meta_key();
+        let shift = ev.shift_key();
+        let multiple = self.selection_mode.get() == SelectionMode::Multiple;
+        let branch = (callbacks.has_children)(&current);
+        match &*ev.key() {
+            "ArrowRight" if !toggle && !shift && branch => {
+                if !self.is_expanded(&current) {
+                    self.set_expanded(&current, true);
+                } else if order
+                    .get(active + 1)
+                    .is_some_and(|next| self.parent(next).as_ref() == Some(&current))
+                {
+                    self.roving.focus(active + 1);
+                }
+            }
+            "ArrowLeft" if !toggle && !shift => {
+                if branch && self.is_expanded(&current) {
+                    self.set_expanded(&current, false);
+                } else if let Some(index) = self
+                    .parent(&current)
+                    .and_then(|parent| order.iter().position(|n| *n == parent))
+                {
+                    self.roving.focus(index);
+                }
+            }
+            key @ ("ArrowDown" | "ArrowUp") if shift && !toggle && multiple => {
+                let next = if key == "ArrowDown" {
+                    Some(active + 1).filter(|&next| next < order.len())
+                } else {
+                    active.checked_sub(1)
+                };
+                if let Some(next) = next {
+                    self.roving.focus(next);
+                    self.select(
+                        &order[next],
+                        ClickModifiers {
+                            toggle: true,
+                            shift: false,
+                        },
+                    );
+                }
+            }
+            " " | "Enter" => self.select(&current, ClickModifiers { toggle, shift }),
+            "*" => {
+                let siblings: Vec<K> = {
+                    let inner = self.inner.borrow();
+                    match self.parent(&current) {
+                        Some(parent) => inner.children(&parent).map_or(Vec::new(), |c| c.to_vec()),
+                        None => inner.roots.to_vec(),
+                    }
+                };
+                self.expanded.borrow_mut().extend(
+                    siblings
+                        .into_iter()
+                        .filter(|sibling| (callbacks.has_children)(sibling)),
+                );
+            }
+            "a" | "A" if toggle && multiple => self.set_selected(order),
+            _ => return,
+        }
+        ev.prevent_default();
+    }
+}
+
+impl<K: Eq + Hash + Clone> Inner<K> {
+    fn node_mut(&mut self, node: &K) -> &mut NodeState<K> {
+        self.nodes.entry(node.clone()).or_insert_with(|| NodeState {
+            parent: None,
+            children: None,
+        })
+    }
+    fn children(&self, node: &K) -> Option<&ListModel<K>> {
+        self.nodes.get(node)?.children.as_ref()
+    }
+    fn set_children(&mut self, parent: &K, children: Vec<K>) {
+        for child in &children {
+            self.node_mut(child).parent = Some(parent.clone());
+        }
+        // the list keeps the children that are still there, even with a new model
+        self.node_mut(parent).children = Some(ListModel::from(children));
+    }
+    /// Take a node out of its parent's children (or the roots), and forget it
+    /// and all its loaded descendants. Returns the nodes forgotten.
+    fn remove_subtree(&mut self, node: &K) -> Vec<K> {
+        let Some(parent) = self.nodes.get(node).map(|state| state.parent.clone()) else {
+            return Vec::new();
+        };
+        let siblings = match &parent {
+            Some(parent) => self.node_mut(parent).children.as_mut(),
+            None => Some(&mut self.roots),
+        };
+        if let Some(siblings) = siblings {
+            if let Some(index) = siblings.iter().position(|s| s == node) {
+                siblings.remove(index);
+            }
+        }
+        let removed = visible_nodes(std::slice::from_ref(node), |n| self.children(n));
+        for node in &removed {
+            self.nodes.remove(node);
+        }
+        removed
+    }
+}
+
+/// The nodes in display order: each node followed by its descendants,
+/// for those whose children `shown_children` returns.
+fn visible_nodes<'a, K: Clone + 'a>(
+    roots: &'a [K],
+    shown_children: impl Fn(&K) -> Option<&'a ListModel<K>>,
+) -> Vec<K> {
+    let mut nodes = Vec::new();
+    let mut stack: Vec<&K> = roots.iter().rev().collect();
+    while let Some(node) = stack.pop() {
+        nodes.push(node.clone());
+        if let Some(children) = shown_children(node) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_in_display_order() {
+        // 1
+        // ├ 2
+        // │ └ 3
+        // └ 4
+        // 5
+        // └ 6
+        let children: HashMap<u32, ListModel<u32>> = [
+            (1, ListModel::from(vec![2, 4])),
+            (2, ListModel::from(vec![3])),
+            (5, ListModel::from(vec![6])),
+        ]
+        .into_iter()
+        .collect();
+        let roots = [1, 5];
+        assert_eq!(
+            visible_nodes(&roots, |node| children.get(node)),
+            [1, 2, 3, 4, 5, 6]
+        );
+        // 2 and 5 collapsed
+        assert_eq!(
+            visible_nodes(&roots, |node| children.get(node).filter(|_| *node == 1)),
+            [1, 2, 4, 5]
+        );
+    }
+
+    #[test]
+    fn removes_subtree() {
+        let mut inner = Inner {
+            roots: ListModel::from(vec![1, 5]),
+            nodes: HashMap::new(),
+        };
+        inner.node_mut(&1);
+        inner.node_mut(&5);
+        inner.set_children(&1, vec![2, 4]);
+        inner.set_children(&2, vec![3]);
+        inner.set_children(&5, vec![6]);
+        let mut removed = inner.remove_subtree(&2);
+        removed.sort();
+        assert_eq!(removed, [2, 3]);
+        assert_eq!(&inner.children(&1).unwrap()[..], [4]);
+        let mut left: Vec<u32> = inner.nodes.keys().copied().collect();
+        left.sort();
+        assert_eq!(left, [1, 4, 5, 6]);
+        assert_eq!(inner.remove_subtree(&5), [5, 6]);
+        assert_eq!(&inner.roots[..], [1]);
+        assert_eq!(inner.remove_subtree(&5), [] as [u32; 0]);
+    }
+}
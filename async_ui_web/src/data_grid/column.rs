@@ -0,0 +1,72 @@
+use std::{cmp::Ordering, future::Future, pin::Pin};
+
+pub(super) type CellFuture<'c> = Pin<Box<dyn Future<Output = ()> + 'c>>;
+pub(super) type Comparator<'c, Row> = Box<dyn Fn(&Row, &Row) -> Ordering + 'c>;
+
+/**
+A column of a [DataGrid][super::DataGrid]: its header, how to render its cells,
+and how to sort by it.
+
+```
+# use async_ui_web::{data_grid::Column, prelude_traits::*};
+struct Person {
+    name: String,
+    age: u32,
+}
+
+let name = Column::new("Name", |person: &Person| person.name.render())
+    .with_sort_key(|person| person.name.clone());
+let age = Column::new("Age", |person: &Person| person.age.to_string().render())
+    .with_sort_key(|person| person.age)
+    .with_width(80.0);
+```
+
+The cell future can't borrow the row (rows can be replaced while it is shown),
+so copy or clone what it needs out of the row.
+ */
+pub struct Column<'c, Row> {
+    pub(super) header: String,
+    pub(super) cell: Box<dyn Fn(&Row) -> CellFuture<'c> + 'c>,
+    pub(super) compare: Option<Comparator<'c, Row>>,
+    pub(super) width: Option<f64>,
+    pub(super) resizable: bool,
+}
+
+impl<'c, Row> Column<'c, Row> {
+    /// A column with the given header text, rendering each cell with the given function.
+    pub fn new<F: Future + 'c>(header: &str, cell: impl Fn(&Row) -> F + 'c) -> Self {
+        Self {
+            header: header.to_owned(),
+            cell: Box::new(move |row| {
+                let future = cell(row);
+                Box::pin(async move {
+                    future.await;
+                })
+            }),
+            compare: None,
+            width: None,
+            resizable: true,
+        }
+    }
+    /// Make the column sortable, comparing rows with the given function.
+    pub fn with_sort_by(mut self, compare: impl Fn(&Row, &Row) -> Ordering + 'c) -> Self {
+        self.compare = Some(Box::new(compare));
+        self
+    }
+    /// Make the column sortable, ordering rows by the key the given function extracts.
+    pub fn with_sort_key<K: Ord>(self, key: impl Fn(&Row) -> K + 'c) -> Self {
+        self.with_sort_by(move |a, b| key(a).cmp(&key(b)))
+    }
+    /// Set the initial width of the column, in CSS pixels.
+    /// Without it, the browser sizes the column to fit.
+    pub fn with_width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+    /// Whether the user can resize the column by dragging its header's edge.
+    /// Defaults to `true`.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+}
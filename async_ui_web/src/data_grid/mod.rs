@@ -0,0 +1,719 @@
+//! A table for browsing many rows of data: [DataGrid].
+//!
+//! Columns are declared up front with [Column], which says what goes in the
+//! header, how to render each cell, and how to sort by the column.
+//! The grid takes care of sorting when a header is clicked, filtering,
+//! resizing and reordering columns, selecting rows, and showing the rows
+//! a page at a time or (for very many rows) only those scrolled into view.
+
+mod column;
+mod view;
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::HashSet,
+    future::{pending, Future},
+    ops::Range,
+    pin::Pin,
+};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::combinators::{join, race, UiFutureExt};
+use async_ui_web_html::{
+    events::{EmitElementEvent, EmitHtmlElementEvent},
+    nodes::{Button, Div, Span, TBody, THead, Table, Td, Th, Tr},
+};
+use futures_lite::{Stream, StreamExt};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{DragEvent, HtmlElement, MouseEvent, Node};
+
+use crate::{
+    gestures::{EmitGestureEvent, GesturePhase},
+    lists::{ListModel, ModeledList, VirtualizedList},
+    selection::{click_select, ClickModifiers},
+    shortcuts::ShortcutClassList,
+    NoChild,
+};
+
+pub use crate::selection::SelectionMode;
+pub use column::Column;
+use view::{move_item, page_count, page_range, sync_model, visible_rows};
+
+/// The direction a [DataGrid] is sorted in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// Columns can't be resized narrower than this, in CSS pixels.
+const MIN_COLUMN_WIDTH: f64 = 24.0;
+
+type RowFuture<'c> = Pin<Box<dyn Future<Output = ()> + 'c>>;
+type Filter<'c, Row> = Box<dyn Fn(&Row) -> bool + 'c>;
+
+/**
+A table of rows, with sorting, filtering, column resizing and reordering,
+row selection, and pagination or virtualization.
+
+```
+# use async_ui_web::{data_grid::{Column, DataGrid, SelectionMode}, html::Button, join, prelude_traits::*};
+struct Person {
+    name: String,
+    age: u32,
+}
+
+# let _ = async {
+let grid = DataGrid::new([
+    Column::new("Name", |person: &Person| person.name.render())
+        .with_sort_key(|person| person.name.clone()),
+    Column::new("Age", |person: &Person| person.age.to_string().render())
+        .with_sort_key(|person| person.age),
+]);
+grid.set_rows((0..95).map(|n| Person {
+    name: format!("Person {n}"),
+    age: n,
+}));
+grid.set_filter(|person| person.age >= 18);
+grid.set_selection_mode(SelectionMode::Multiple);
+grid.set_page_size(Some(20));
+
+let next_page = Button::new();
+join((
+    grid.render(),
+    next_page.render("Next".render()),
+    async {
+        loop {
+            next_page.until_click().await;
+            grid.set_page(grid.page() + 1);
+        }
+    },
+))
+.await;
+# };
+```
+
+Columns are identified by their position in the list given to [new][Self::new],
+even after the user reorders them. Rows are identified by their index in the
+rows given to [set_rows][Self::set_rows], whatever the sorting and filtering.
+
+The grid is a `<table>` inside a `<div class="data-grid">`.
+The header row sticks to the top of the scrolling area.
+Headers of sortable columns contain a button that cycles through ascending,
+descending, and unsorted, and have the `aria-sort` attribute.
+Dragging a header onto another moves the column there, and dragging the
+`<div class="data-grid-resize-handle">` at the end of a header resizes the column
+(give the table `table-layout: fixed` for widths to be followed exactly).
+Selected rows have the `selected` class.
+
+When rows are sorted, filtered, or paged, rows that stay on screen are moved
+(with [ModeledList]) rather than rendered again.
+With [set_virtualized][Self::set_virtualized], only the rows scrolled into view
+are rendered (with [VirtualizedList]); the `data-grid` div must then have a fixed height.
+ */
+pub struct DataGrid<'c, Row> {
+    root: Div,
+    columns: Vec<Column<'c, Row>>,
+    headers: Vec<Header>,
+    /// Column indices, in display order.
+    order: ReactiveCell<Vec<usize>>,
+    rows: ReactiveCell<Vec<Row>>,
+    filter: RefCell<Option<Filter<'c, Row>>>,
+    settings: ReactiveCell<Settings>,
+    /// Indices of the rows that pass the filter, sorted.
+    view: ReactiveCell<Vec<usize>>,
+    selection: ReactiveCell<HashSet<usize>>,
+    selection_mode: Cell<SelectionMode>,
+    /// The row that Shift-clicks select from.
+    anchor: Cell<Option<usize>>,
+    /// The column whose header is being dragged.
+    dragged_column: Cell<Option<usize>>,
+}
+
+struct Header {
+    th: Th,
+    sort_button: Option<Button>,
+    resize_handle: Option<Div>,
+}
+
+#[derive(Clone, Copy)]
+struct Settings {
+    sort: Option<(usize, SortDirection)>,
+    page: usize,
+    page_size: Option<usize>,
+    virtualized: bool,
+}
+
+impl<'c, Row: 'c> DataGrid<'c, Row> {
+    /// Create a grid with the given columns, and no rows.
+    pub fn new(columns: impl IntoIterator<Item = Column<'c, Row>>) -> Self {
+        let columns: Vec<_> = columns.into_iter().collect();
+        let headers = columns.iter().map(Header::new).collect();
+        let root = Div::new();
+        root.add_class("data-grid");
+        Self {
+            root,
+            order: ReactiveCell::new((0..columns.len()).collect()),
+            columns,
+            headers,
+            rows: ReactiveCell::new(Vec::new()),
+            filter: RefCell::new(None),
+            settings: ReactiveCell::new(Settings {
+                sort: None,
+                page: 0,
+                page_size: None,
+                virtualized: false,
+            }),
+            view: ReactiveCell::new(Vec::new()),
+            selection: ReactiveCell::new(HashSet::new()),
+            selection_mode: Cell::new(SelectionMode::None),
+            anchor: Cell::new(None),
+            dragged_column: Cell::new(None),
+        }
+    }
+    /// Replace all the rows. This clears the selection.
+    pub fn set_rows(&self, rows: impl IntoIterator<Item = Row>) {
+        *self.rows.borrow_mut() = rows.into_iter().collect();
+        self.anchor.set(None);
+        if !self.selection.borrow().is_empty() {
+            self.selection.borrow_mut().clear();
+        }
+    }
+    /// Modify the rows in place.
+    ///
+    /// The selection is kept (minus rows that no longer exist), so if you insert
+    /// or remove rows, you may want to [fix it up][Self::set_selected] afterwards.
+    pub fn update_rows(&self, update: impl FnOnce(&mut Vec<Row>)) {
+        update(&mut self.rows.borrow_mut());
+        let len = self.rows.borrow().len();
+        if self.selection.borrow().iter().any(|&row| row >= len) {
+            self.selection.borrow_mut().retain(|&row| row < len);
+        }
+    }
+    /// Get the rows, in their original order.
+    pub fn rows(&self) -> Ref<'_, [Row]> {
+        Ref::map(self.rows.borrow(), |rows| &rows[..])
+    }
+    /// Only show rows for which the given function returns `true`.
+    /// This replaces any previous filter, and goes back to the first page.
+    ///
+    /// Call this again whenever the outcome of the filter changes (for example,
+    /// when the user types in a search box).
+    pub fn set_filter(&self, filter: impl Fn(&Row) -> bool + 'c) {
+        *self.filter.borrow_mut() = Some(Box::new(filter));
+        self.settings.borrow_mut().page = 0;
+    }
+    /// Show all the rows again.
+    pub fn clear_filter(&self) {
+        *self.filter.borrow_mut() = None;
+        self.settings.borrow_mut().page = 0;
+    }
+    /// Get the column the rows are sorted by, and in what direction.
+    pub fn sort(&self) -> Option<(usize, SortDirection)> {
+        self.settings.borrow().sort
+    }
+    /// Sort the rows by the given column, or show them in their original order with `None`.
+    ///
+    /// Columns without a comparator (see [Column::with_sort_by]) and out-of-range
+    /// indices are treated like `None`.
+    pub fn set_sort(&self, sort: Option<(usize, SortDirection)>) {
+        let sort = sort.filter(|&(column, _)| {
+            self.columns
+                .get(column)
+                .is_some_and(|column| column.compare.is_some())
+        });
+        self.settings.borrow_mut().sort = sort;
+    }
+    /// Get the column indices, in the order the columns are displayed.
+    pub fn column_order(&self) -> Vec<usize> {
+        self.order.borrow().clone()
+    }
+    /// Move the column displayed at position `from` to position `to`.
+    ///
+    /// Does nothing if either position is out of range.
+    pub fn move_column(&self, from: usize, to: usize) {
+        let len = self.order.borrow().len();
+        if from != to && from < len && to < len {
+            move_item(&mut self.order.borrow_mut(), from, to);
+        }
+    }
+    /// Set the width of the given column, in CSS pixels.
+    ///
+    /// Does nothing if the column index is out of range.
+    pub fn set_column_width(&self, column: usize, width: f64) {
+        if let Some(header) = self.headers.get(column) {
+            header.set_width(width);
+        }
+    }
+    /// Set what clicking a row does. Changing the mode clears the selection.
+    pub fn set_selection_mode(&self, mode: SelectionMode) {
+        if self.selection_mode.replace(mode) != mode {
+            self.anchor.set(None);
+            self.selection.borrow_mut().clear();
+        }
+    }
+    /// Get the indices of the selected rows, in ascending order.
+    pub fn selected(&self) -> Vec<usize> {
+        let mut selected: Vec<_> = self.selection.borrow().iter().copied().collect();
+        selected.sort_unstable();
+        selected
+    }
+    /// Select exactly the rows with the given indices.
+    pub fn set_selected(&self, rows: impl IntoIterator<Item = usize>) {
+        let len = self.rows.borrow().len();
+        *self.selection.borrow_mut() = rows.into_iter().filter(|&row| row < len).collect();
+    }
+    /// Get a [Stream] that fires every time the selection changes,
+    /// whether by the user or by the methods on this grid.
+    pub fn until_selection_change(&self) -> impl Future<Output = ()> + Stream<Item = ()> + '_ {
+        self.selection.until_change()
+    }
+    /// Show at most this many rows at a time, or all of them with `None` (the default).
+    pub fn set_page_size(&self, page_size: Option<usize>) {
+        let mut settings = self.settings.borrow_mut();
+        settings.page_size = page_size;
+        settings.page = 0;
+    }
+    /// Get the current page, counting from 0.
+    pub fn page(&self) -> usize {
+        let settings = *self.settings.borrow();
+        settings.page.min(self.page_count() - 1)
+    }
+    /// Go to the given page, counting from 0. Pages past the end show the last page.
+    pub fn set_page(&self, page: usize) {
+        let page = page.min(self.page_count() - 1);
+        if self.settings.borrow().page != page {
+            self.settings.borrow_mut().page = page;
+        }
+    }
+    /// Get the number of pages of rows that pass the filter. At least 1.
+    pub fn page_count(&self) -> usize {
+        let rows = self.rows.borrow();
+        let filter = self.filter.borrow();
+        let len = match filter.as_deref() {
+            Some(filter) => rows.iter().filter(|row| filter(row)).count(),
+            None => rows.len(),
+        };
+        page_count(len, self.settings.borrow().page_size)
+    }
+    /// Render only the rows scrolled into view, instead of pages.
+    ///
+    /// The rows must all have the same height, and the grid must be given a fixed
+    /// height (the grid scrolls within it).
+    pub fn set_virtualized(&self, virtualized: bool) {
+        if self.settings.borrow().virtualized != virtualized {
+            self.settings.borrow_mut().virtualized = virtualized;
+        }
+    }
+    /// Put the grid on the screen.
+    ///
+    /// This async method never completes.
+    pub async fn render(&self) {
+        let table = Table::new();
+        let thead = THead::new();
+        let head_row = Tr::new();
+        let tbody = TBody::new();
+        let style = thead.style();
+        style.set_property("position", "sticky").unwrap_throw();
+        style.set_property("top", "0").unwrap_throw();
+        style.set_property("z-index", "1").unwrap_throw();
+        join((
+            self.root.render(table.render(join((
+                thead.render(head_row.render(NoChild)),
+                tbody.render(self.render_body()),
+            )))),
+            self.sync_headers(&head_row, &table),
+            join(
+                (0..self.columns.len())
+                    .map(|column| self.handle_header(column))
+                    .collect::<Vec<_>>(),
+            ),
+        ))
+        .await;
+    }
+    async fn until_view_change(&self) {
+        race((self.settings.until_change(), self.rows.until_change())).await;
+    }
+    /// Recompute which rows to show. Returns the range of the view on the current page.
+    fn refresh_view(&self) -> Range<usize> {
+        let rows = self.rows.borrow();
+        let filter = self.filter.borrow();
+        let settings = *self.settings.borrow();
+        let sort = settings.sort.and_then(|(column, direction)| {
+            let compare = self.columns[column].compare.as_deref()?;
+            Some((compare, direction))
+        });
+        let view = visible_rows(&rows, filter.as_deref(), sort);
+        let range = if settings.virtualized {
+            0..view.len()
+        } else {
+            page_range(view.len(), settings.page_size, settings.page)
+        };
+        if *self.view.borrow() != view {
+            *self.view.borrow_mut() = view;
+        }
+        range
+    }
+    async fn render_body(&self) {
+        loop {
+            let virtualized = self.settings.borrow().virtualized;
+            race((
+                async {
+                    if virtualized {
+                        self.render_virtualized().await;
+                    } else {
+                        self.render_paged().await;
+                    }
+                },
+                async {
+                    while self.settings.borrow().virtualized == virtualized {
+                        self.settings.until_change().await;
+                    }
+                },
+            ))
+            .await;
+        }
+    }
+    async fn render_paged(&self) {
+        let list = ModeledList::new(|&row: &usize| Box::pin(self.render_row(row)) as RowFuture<'_>);
+        let mut model = ListModel::new();
+        join((list.render(), async {
+            loop {
+                let range = self.refresh_view();
+                sync_model(&mut model, &self.view.borrow()[range]);
+                list.update(&model);
+                self.until_view_change().await;
+            }
+        }))
+        .await;
+    }
+    async fn render_virtualized(&self) {
+        let root: &HtmlElement = &self.root;
+        root.style().set_property("overflow", "auto").unwrap_throw();
+        let _overflow = scopeguard::guard((), |_| {
+            root.style().remove_property("overflow").unwrap_throw();
+        });
+        let list = VirtualizedList::new(
+            root,
+            Tr::new().element.into(),
+            Tr::new().element.into(),
+            |position| Box::pin(self.render_at(position)) as RowFuture<'_>,
+        );
+        join((list.render(), async {
+            loop {
+                let range = self.refresh_view();
+                list.set_num_items(range.len());
+                self.until_view_change().await;
+            }
+        }))
+        .await;
+    }
+    /// Render whatever row is at the given position of the view.
+    async fn render_at(&self, position: usize) {
+        loop {
+            let row = self.view.borrow().get(position).copied();
+            race((
+                async {
+                    match row {
+                        Some(row) => self.render_row(row).await,
+                        None => pending().await,
+                    }
+                },
+                async {
+                    while self.view.borrow().get(position).copied() == row {
+                        self.view.until_change().await;
+                    }
+                },
+            ))
+            .await;
+        }
+    }
+    async fn render_row(&self, row: usize) {
+        let tr = Tr::new();
+        tr.render(self.render_cells(row))
+            .meanwhile(join((
+                self.sync_selected(&tr, row),
+                self.handle_clicks(&tr, row),
+            )))
+            .await;
+    }
+    /// Render the cells of the row, again whenever the rows or the column order change.
+    async fn render_cells(&self, row: usize) {
+        loop {
+            let cells: Vec<_> = {
+                let rows = self.rows.borrow();
+                match rows.get(row) {
+                    Some(data) => self
+                        .order
+                        .borrow()
+                        .iter()
+                        .map(|&column| {
+                            let cell = (self.columns[column].cell)(data);
+                            async move {
+                                Td::new().render(cell).await;
+                            }
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                }
+            };
+            race((
+                async {
+                    join(cells).await;
+                    pending::<()>().await;
+                },
+                async {
+                    race((self.rows.until_change(), self.order.until_change())).await;
+                },
+            ))
+            .await;
+        }
+    }
+    async fn sync_selected(&self, tr: &Tr, row: usize) {
+        loop {
+            let selected = self.selection.borrow().contains(&row);
+            tr.set_class("selected", selected);
+            if self.selection_mode.get() == SelectionMode::None {
+                tr.remove_attribute("aria-selected").unwrap_throw();
+            } else {
+                tr.set_attribute("aria-selected", if selected { "true" } else { "false" })
+                    .unwrap_throw();
+            }
+            self.selection.until_change().await;
+        }
+    }
+    async fn handle_clicks(&self, tr: &Tr, row: usize) {
+        let mut clicks = tr.until_click();
+        while let Some(ev) = clicks.next().await {
+            self.select_by_click(row, &ev);
+        }
+    }
+    fn select_by_click(&self, row: usize, ev: &MouseEvent) {
+        let mode = self.selection_mode.get();
+        if mode == SelectionMode::None {
+            return;
+        }
+        let mut selection = self.selection.borrow().clone();
+        let mut anchor = self.anchor.get();
+        click_select(
+            &mut selection,
+            &mut anchor,
+            &self.view.borrow(),
+            &row,
+            mode,
+            ClickModifiers {
+                toggle: ev.ctrl_key() || ev.meta_key(),
+                shift: ev.shift_key(),
+            },
+        );
+        self.anchor.set(anchor);
+        if *self.selection.borrow() != selection {
+            *self.selection.borrow_mut() = selection;
+        }
+    }
+    /// Keep the header cells in column order, and the ARIA attributes up to date.
+    async fn sync_headers(&self, head_row: &Tr, table: &Table) {
+        loop {
+            self.update_headers(head_row, table);
+            race((
+                self.order.until_change(),
+                self.settings.until_change(),
+                self.selection.until_change(),
+            ))
+            .await;
+        }
+    }
+    fn update_headers(&self, head_row: &Tr, table: &Table) {
+        for &column in self.order.borrow().iter() {
+            // appending an existing child moves it, so this puts the headers in order
+            head_row
+                .append_child(&self.headers[column].th)
+                .unwrap_throw();
+        }
+        let sort = self.settings.borrow().sort;
+        for (column, header) in self.headers.iter().enumerate() {
+            if header.sort_button.is_some() {
+                let value = match sort {
+                    Some((sorted, SortDirection::Ascending)) if sorted == column => "ascending",
+                    Some((sorted, SortDirection::Descending)) if sorted == column => "descending",
+                    _ => "none",
+                };
+                header.th.set_attribute("aria-sort", value).unwrap_throw();
+            }
+        }
+        match self.selection_mode.get() {
+            SelectionMode::None => {
+                table.remove_attribute("role").unwrap_throw();
+                table
+                    .remove_attribute("aria-multiselectable")
+                    .unwrap_throw();
+            }
+            mode => {
+                table.set_attribute("role", "grid").unwrap_throw();
+                table
+                    .set_attribute(
+                        "aria-multiselectable",
+                        if mode == SelectionMode::Multiple {
+                            "true"
+                        } else {
+                            "false"
+                        },
+                    )
+                    .unwrap_throw();
+            }
+        }
+    }
+    async fn handle_header(&self, column: usize) {
+        join((
+            self.sort_on_click(column),
+            self.resize_on_drag(column),
+            self.reorder_on_drag(column),
+        ))
+        .await;
+    }
+    async fn sort_on_click(&self, column: usize) {
+        let Some(button) = &self.headers[column].sort_button else {
+            return;
+        };
+        let mut clicks = button.until_click();
+        while clicks.next().await.is_some() {
+            let sort = match self.sort() {
+                Some((sorted, SortDirection::Ascending)) if sorted == column => {
+                    Some((column, SortDirection::Descending))
+                }
+                Some((sorted, SortDirection::Descending)) if sorted == column => None,
+                _ => Some((column, SortDirection::Ascending)),
+            };
+            self.set_sort(sort);
+        }
+    }
+    async fn resize_on_drag(&self, column: usize) {
+        let header = &self.headers[column];
+        let Some(handle) = &header.resize_handle else {
+            return;
+        };
+        let mut pans = handle.until_pan();
+        let mut start_width = 0.0;
+        while let Some(pan) = pans.next().await {
+            if pan.phase == GesturePhase::Start {
+                start_width = header.th.get_bounding_client_rect().width();
+            }
+            header.set_width((start_width + pan.total_dx).max(MIN_COLUMN_WIDTH));
+        }
+    }
+    async fn reorder_on_drag(&self, column: usize) {
+        let th = &self.headers[column].th;
+        let mut starts = th.until_dragstart();
+        let mut overs = th.until_dragover();
+        let mut drops = th.until_drop();
+        let mut ends = th.until_dragend();
+        loop {
+            race((
+                async {
+                    let ev = starts.next().await.unwrap_throw();
+                    self.start_column_drag(column, &ev);
+                },
+                async {
+                    let ev = overs.next().await.unwrap_throw();
+                    // allow dropping here
+                    if self.dragged_column.get().is_some() {
+                        ev.prevent_default();
+                    }
+                },
+                async {
+                    let ev = drops.next().await.unwrap_throw();
+                    if let Some(dragged) = self.dragged_column.take() {
+                        ev.prevent_default();
+                        let order = self.column_order();
+                        let position = |column| order.iter().position(|&c| c == column);
+                        if let (Some(from), Some(to)) = (position(dragged), position(column)) {
+                            self.move_column(from, to);
+                        }
+                    }
+                },
+                async {
+                    ends.next().await;
+                    self.dragged_column.set(None);
+                },
+            ))
+            .await;
+        }
+    }
+    fn start_column_drag(&self, column: usize, ev: &DragEvent) {
+        let header = &self.headers[column];
+        let from_handle = header.resize_handle.as_ref().is_some_and(|handle| {
+            let handle: &Node = handle.as_ref();
+            ev.target()
+                .and_then(|target| target.dyn_into::<Node>().ok())
+                .is_some_and(|target| handle.contains(Some(&target)))
+        });
+        if from_handle {
+            // that's a resize, not a reorder
+            ev.prevent_default();
+            return;
+        }
+        self.dragged_column.set(Some(column));
+        if let Some(data) = ev.data_transfer() {
+            data.set_effect_allowed("move");
+            // some browsers only start the drag if there's data
+            data.set_data("text/plain", &self.columns[column].header)
+                .unwrap_throw();
+        }
+    }
+}
+
+impl Header {
+    fn new<Row>(column: &Column<'_, Row>) -> Self {
+        let th = Th::new();
+        th.set_attribute("scope", "col").unwrap_throw();
+        th.set_draggable(true);
+        th.style()
+            .set_property("position", "relative")
+            .unwrap_throw();
+        let sort_button = column.compare.as_ref().map(|_| {
+            let button = Button::new();
+            button.set_type("button");
+            button.set_text_content(Some(&column.header));
+            th.append_child(&button).unwrap_throw();
+            button
+        });
+        if sort_button.is_none() {
+            let label = Span::new();
+            label.set_text_content(Some(&column.header));
+            th.append_child(&label).unwrap_throw();
+        }
+        let resize_handle = column.resizable.then(|| {
+            let handle = Div::new();
+            handle.add_class("data-grid-resize-handle");
+            let style = handle.style();
+            for (property, value) in [
+                ("position", "absolute"),
+                ("top", "0"),
+                ("bottom", "0"),
+                ("inset-inline-end", "0"),
+                ("width", "6px"),
+                ("cursor", "col-resize"),
+                ("touch-action", "none"),
+            ] {
+                style.set_property(property, value).unwrap_throw();
+            }
+            th.append_child(&handle).unwrap_throw();
+            handle
+        });
+        let header = Self {
+            th,
+            sort_button,
+            resize_handle,
+        };
+        if let Some(width) = column.width {
+            header.set_width(width);
+        }
+        header
+    }
+    fn set_width(&self, width: f64) {
+        self.th
+            .style()
+            .set_property("width", &format!("{width}px"))
+            .unwrap_throw();
+    }
+}
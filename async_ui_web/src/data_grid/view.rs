@@ -0,0 +1,141 @@
+//! Which rows to show, in what order. No DOM here.
+
+use std::{cmp::Ordering, collections::HashSet, ops::Range};
+
+use crate::lists::ListModel;
+
+use super::SortDirection;
+
+type Compare<'a, Row> = &'a dyn Fn(&Row, &Row) -> Ordering;
+
+/// Indices of the rows that pass the filter, sorted with the comparator.
+///
+/// The sort is stable, so rows that compare equal keep their original order.
+pub(crate) fn visible_rows<Row>(
+    rows: &[Row],
+    filter: Option<&dyn Fn(&Row) -> bool>,
+    sort: Option<(Compare<'_, Row>, SortDirection)>,
+) -> Vec<usize> {
+    let mut view: Vec<usize> = (0..rows.len())
+        .filter(|&index| filter.is_none_or(|filter| filter(&rows[index])))
+        .collect();
+    if let Some((compare, direction)) = sort {
+        view.sort_by(|&a, &b| {
+            let ordering = compare(&rows[a], &rows[b]);
+            match direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+    }
+    view
+}
+
+/// Number of pages needed for `len` rows. At least 1, even with no rows.
+pub(crate) fn page_count(len: usize, page_size: Option<usize>) -> usize {
+    match page_size {
+        Some(size) if size > 0 => len.div_ceil(size).max(1),
+        _ => 1,
+    }
+}
+
+/// The range of the view that is on the given page.
+/// Pages past the end show the last page.
+pub(crate) fn page_range(len: usize, page_size: Option<usize>, page: usize) -> Range<usize> {
+    match page_size {
+        Some(size) if size > 0 => {
+            let page = page.min(page_count(len, page_size) - 1);
+            let start = page * size;
+            start..(start + size).min(len)
+        }
+        _ => 0..len,
+    }
+}
+
+/// Edit the model to contain exactly `target`, in order,
+/// keeping the rows that are already there so they're moved rather than re-rendered.
+pub(crate) fn sync_model(model: &mut ListModel<usize>, target: &[usize]) {
+    let wanted: HashSet<usize> = target.iter().copied().collect();
+    let mut index = 0;
+    while index < model.len() {
+        if wanted.contains(&model[index]) {
+            index += 1;
+        } else {
+            model.remove(index);
+        }
+    }
+    for (index, &row) in target.iter().enumerate() {
+        if model.get(index) == Some(&row) {
+            continue;
+        }
+        match model[index..].iter().position(|&r| r == row) {
+            Some(offset) => model.move_item(index + offset, index),
+            None => model.insert(index, row),
+        }
+    }
+}
+
+/// Move the item at `from` to be at `to`, shifting the ones in between.
+pub(crate) fn move_item<T>(items: &mut [T], from: usize, to: usize) {
+    if from < to {
+        items[from..=to].rotate_left(1);
+    } else {
+        items[to..=from].rotate_right(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_and_sort() {
+        let rows = ["pear", "apple", "fig", "banana", "kiwi"];
+        let by_len = |a: &&str, b: &&str| a.len().cmp(&b.len());
+        assert_eq!(visible_rows(&rows, None, None), [0, 1, 2, 3, 4]);
+        // stable: "pear" and "kiwi" keep their order
+        assert_eq!(
+            visible_rows(&rows, None, Some((&by_len, SortDirection::Ascending))),
+            [2, 0, 4, 1, 3]
+        );
+        let no_a = |row: &&str| !row.contains('a');
+        assert_eq!(
+            visible_rows(
+                &rows,
+                Some(&no_a),
+                Some((&by_len, SortDirection::Descending))
+            ),
+            [4, 2]
+        );
+    }
+
+    #[test]
+    fn pages() {
+        assert_eq!(page_count(0, Some(10)), 1);
+        assert_eq!(page_count(25, Some(10)), 3);
+        assert_eq!(page_count(25, None), 1);
+        assert_eq!(page_range(25, Some(10), 1), 10..20);
+        assert_eq!(page_range(25, Some(10), 2), 20..25);
+        // past the end: the last page
+        assert_eq!(page_range(25, Some(10), 7), 20..25);
+        assert_eq!(page_range(25, None, 3), 0..25);
+    }
+
+    #[test]
+    fn syncs_model() {
+        let mut model = ListModel::from(vec![1, 2, 3, 4, 5]);
+        for target in [&[5, 3, 1, 6][..], &[], &[2, 1], &[1, 2, 3]] {
+            sync_model(&mut model, target);
+            assert_eq!(&model[..], target);
+        }
+    }
+
+    #[test]
+    fn moves_items() {
+        let mut items = [0, 1, 2, 3];
+        move_item(&mut items, 0, 2);
+        assert_eq!(items, [1, 2, 0, 3]);
+        move_item(&mut items, 3, 0);
+        assert_eq!(items, [3, 1, 2, 0]);
+    }
+}
@@ -0,0 +1,345 @@
+//! Keyboard focus management, for building accessible dialogs, menus, and toolbars.
+//!
+//! *   [FocusTrap] keeps Tab focus inside a part of the page (such as a modal dialog),
+//!     and gives focus back to where it was when the trap goes away.
+//! *   [RovingTabindex] lets the user move focus across a group of elements
+//!     (such as the buttons of a toolbar) with the arrow keys,
+//!     while the group only takes one stop in the Tab order.
+
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    ops::Deref,
+};
+
+use async_ui_web_core::{combinators::UiFutureExt, window::DOCUMENT};
+use async_ui_web_html::{
+    events::{EmitElementEvent, EmitEvent},
+    nodes::Div,
+};
+use futures_lite::StreamExt;
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use web_sys::{Element, EventTarget, HtmlElement, Node};
+
+/// Elements that can receive keyboard focus (unless disabled or hidden).
+const FOCUSABLE_SELECTOR: &str = "a[href], area[href], button:not([disabled]), \
+    input:not([disabled]):not([type=hidden]), select:not([disabled]), \
+    textarea:not([disabled]), iframe, object, embed, summary, \
+    [contenteditable]:not([contenteditable=false]), [tabindex]";
+
+/// Get the elements inside `root` that can be reached with the Tab key, in document order.
+///
+/// Elements that are disabled, not rendered, or have a negative `tabindex` are skipped.
+/// Elements with a positive `tabindex` are treated like those with `tabindex="0"`.
+pub fn tabbable_elements(root: &Element) -> Vec<HtmlElement> {
+    let list = root.query_selector_all(FOCUSABLE_SELECTOR).unwrap_throw();
+    (0..list.length())
+        .filter_map(|i| list.get(i)?.dyn_into::<HtmlElement>().ok())
+        .filter(|elem| elem.tab_index() >= 0 && elem.get_client_rects().length() > 0)
+        .collect()
+}
+
+/// The element that currently has focus, if any.
+fn focused_element() -> Option<HtmlElement> {
+    DOCUMENT
+        .with(|doc| doc.active_element())
+        .and_then(|elem| elem.dyn_into().ok())
+}
+
+/**
+Confine keyboard focus to a subtree.
+
+While rendered, the trap
+*   focuses the first tabbable element inside it (or the trap itself, if there is none),
+*   makes Tab on the last tabbable element go to the first, and Shift+Tab
+    on the first go to the last,
+*   pulls focus back in if it ends up outside (for example, if the user clicks outside).
+
+When the render future is dropped, focus goes back to the element that was
+focused before the trap was rendered.
+
+```
+# use async_ui_web::{focus::FocusTrap, html::{Button, Input}, join, prelude_traits::*};
+# let _ = async {
+let trap = FocusTrap::new();
+let close = Button::new();
+trap.render(
+    close
+        .until_click()
+        .meanwhile(join((Input::new().render(), close.render("Close".render())))),
+)
+.await;
+// the dialog is gone and focus is back where it was
+# };
+```
+
+This type [Deref]s to [Div], the element that contains the trapped subtree.
+ */
+pub struct FocusTrap {
+    container: Div,
+}
+
+impl Default for FocusTrap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for FocusTrap {
+    type Target = Div;
+    fn deref(&self) -> &Self::Target {
+        &self.container
+    }
+}
+
+impl FocusTrap {
+    /// Create a new focus trap. It does nothing until rendered.
+    pub fn new() -> Self {
+        let container = Div::new();
+        // so that the container itself can hold focus if it has nothing tabbable
+        container.set_tab_index(-1);
+        Self { container }
+    }
+    /// Render the given future inside the trap.
+    ///
+    /// The returned future completes when the given future completes.
+    /// Focus is confined while the returned future is alive.
+    ///
+    /// This method should only be called once. It may misbehave otherwise.
+    pub async fn render<F: Future>(&self, c: F) -> F::Output {
+        let previous = focused_element();
+        let _restore = scopeguard::guard(previous, |previous| {
+            if let Some(previous) = previous.filter(|p| p.is_connected()) {
+                previous.focus().ok();
+            }
+        });
+        self.container.render(c).meanwhile(self.trap()).await
+    }
+    fn focus_first(&self) {
+        let target = tabbable_elements(&self.container)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| self.container.element.clone().into());
+        target.focus().ok();
+    }
+    async fn trap(&self) {
+        self.focus_first();
+        let container: &Node = &self.container;
+        let mut keydowns = self.container.until_keydown();
+        let mut focusins = DOCUMENT.with(|doc| {
+            AsRef::<EventTarget>::as_ref(doc).until_event::<web_sys::FocusEvent>("focusin".into())
+        });
+        loop {
+            let escaped = async {
+                let ev = (&mut focusins).await;
+                let inside = ev
+                    .target()
+                    .and_then(|t| t.dyn_into::<Node>().ok())
+                    .is_some_and(|t| container.contains(Some(&t)));
+                !inside
+            };
+            let tab = async {
+                let ev = keydowns.next().await.unwrap_throw();
+                if ev.key() != "Tab" || ev.alt_key() || ev.ctrl_key() || ev.meta_key() {
+                    return false;
+                }
+                let tabbables = tabbable_elements(&self.container);
+                let (Some(first), Some(last)) = (tabbables.first(), tabbables.last()) else {
+                    ev.prevent_default();
+                    return false;
+                };
+                let active = focused_element();
+                let is_active = |elem: &JsValue| {
+                    active
+                        .as_ref()
+                        .is_some_and(|a| AsRef::<JsValue>::as_ref(a) == elem)
+                };
+                let wrap_to = if ev.shift_key() {
+                    (is_active(first) || is_active(self.container.as_ref())).then_some(last)
+                } else {
+                    is_active(last).then_some(first)
+                };
+                if let Some(wrap_to) = wrap_to {
+                    ev.prevent_default();
+                    wrap_to.focus().ok();
+                }
+                false
+            };
+            if async_ui_web_core::combinators::race((escaped, tab)).await {
+                self.focus_first();
+            }
+        }
+    }
+}
+
+/// Which arrow keys move focus in a [RovingTabindex].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Orientation {
+    /// Left and Right.
+    Horizontal,
+    /// Up and Down.
+    Vertical,
+    /// All four arrow keys.
+    #[default]
+    Both,
+}
+
+/**
+Arrow-key navigation across a group of elements.
+
+Only one element of the group (the "active" one) has `tabindex="0"`; the
+others have `tabindex="-1"`. So Tab moves focus into the group and then out
+of it, and the arrow keys (plus Home and End) move focus within it.
+Clicking or otherwise focusing an element of the group makes it active.
+
+```
+# use async_ui_web::{focus::{Orientation, RovingTabindex}, html::{Button, Div}, join, prelude_traits::*};
+# let _ = async {
+let toolbar = Div::new();
+let buttons = [Button::new(), Button::new(), Button::new()];
+let roving = RovingTabindex::new();
+roving.set_orientation(Orientation::Horizontal);
+roving.set_items(buttons.iter().map(|b| b.element.clone().into()));
+join((
+    toolbar.render(join(buttons.each_ref().map(|b| b.render("tool".render())))),
+    roving.run(&toolbar),
+))
+.await;
+# };
+```
+ */
+pub struct RovingTabindex {
+    items: RefCell<Vec<HtmlElement>>,
+    active: Cell<usize>,
+    orientation: Cell<Orientation>,
+    wrap: Cell<bool>,
+}
+
+impl Default for RovingTabindex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RovingTabindex {
+    /// Create a new group with no elements.
+    pub fn new() -> Self {
+        Self {
+            items: RefCell::new(Vec::new()),
+            active: Cell::new(0),
+            orientation: Cell::new(Orientation::default()),
+            wrap: Cell::new(true),
+        }
+    }
+    /// Set the elements in the group, in navigation order.
+    ///
+    /// The active element stays at the same index, or the last element
+    /// if there are fewer elements than before.
+    pub fn set_items(&self, items: impl IntoIterator<Item = HtmlElement>) {
+        *self.items.borrow_mut() = items.into_iter().collect();
+        let len = self.items.borrow().len();
+        self.active
+            .set(self.active.get().min(len.saturating_sub(1)));
+        self.update_tabindex();
+    }
+    /// Which arrow keys move focus. If not set, defaults to [Orientation::Both].
+    pub fn set_orientation(&self, orientation: Orientation) {
+        self.orientation.set(orientation);
+    }
+    /// Whether moving past the last element goes back to the first (and vice versa).
+    /// If not set, defaults to true.
+    pub fn set_wrap(&self, wrap: bool) {
+        self.wrap.set(wrap);
+    }
+    /// The index of the active element.
+    pub fn active(&self) -> usize {
+        self.active.get()
+    }
+    /// Make the element at the given index active, without focusing it.
+    ///
+    /// **Panics** if the index is out of bounds.
+    pub fn set_active(&self, index: usize) {
+        assert!(index < self.items.borrow().len(), "index out of bounds");
+        self.active.set(index);
+        self.update_tabindex();
+    }
+    /// Make the element at the given index active, and focus it.
+    ///
+    /// **Panics** if the index is out of bounds.
+    pub fn focus(&self, index: usize) {
+        self.set_active(index);
+        self.items.borrow()[index].focus().ok();
+    }
+    fn update_tabindex(&self) {
+        let active = self.active.get();
+        for (i, item) in self.items.borrow().iter().enumerate() {
+            item.set_tab_index(if i == active { 0 } else { -1 });
+        }
+    }
+    /// Handle key presses and focus changes for the group.
+    /// `container` must contain all the elements of the group.
+    ///
+    /// This async method never completes.
+    pub async fn run(&self, container: &Element) {
+        let mut keydowns = container.until_keydown();
+        let mut focusins = container.until_focusin();
+        keydowns.set_buffer(8, async_ui_web_html::events::OverflowPolicy::DropOldest);
+        loop {
+            let keydown = async { Some(keydowns.next().await.unwrap_throw()) };
+            let focusin = async {
+                let ev = focusins.next().await.unwrap_throw();
+                let target = ev.target();
+                let index =
+                    self.items.borrow().iter().position(|item| {
+                        Some(AsRef::<EventTarget>::as_ref(item)) == target.as_ref()
+                    });
+                if let Some(index) = index {
+                    self.set_active(index);
+                }
+                None
+            };
+            let Some(ev) = async_ui_web_core::combinators::race((keydown, focusin)).await else {
+                continue;
+            };
+            if ev.alt_key() || ev.ctrl_key() || ev.meta_key() || ev.shift_key() {
+                continue;
+            }
+            let len = self.items.borrow().len();
+            if len == 0 {
+                continue;
+            }
+            // only handle keys from the group's elements, not from e.g. a text box in between
+            let from_item = self
+                .items
+                .borrow()
+                .iter()
+                .any(|item| Some(AsRef::<EventTarget>::as_ref(item)) == ev.target().as_ref());
+            if !from_item {
+                continue;
+            }
+            let orientation = self.orientation.get();
+            let horizontal = orientation != Orientation::Vertical;
+            let vertical = orientation != Orientation::Horizontal;
+            let current = self.active.get();
+            let next = match &*ev.key() {
+                "ArrowLeft" if horizontal => self.step(current, len, false),
+                "ArrowUp" if vertical => self.step(current, len, false),
+                "ArrowRight" if horizontal => self.step(current, len, true),
+                "ArrowDown" if vertical => self.step(current, len, true),
+                "Home" => 0,
+                "End" => len - 1,
+                _ => continue,
+            };
+            ev.prevent_default();
+            self.focus(next);
+        }
+    }
+    fn step(&self, current: usize, len: usize, forward: bool) -> usize {
+        match (forward, self.wrap.get()) {
+            (true, true) => (current + 1) % len,
+            (true, false) => (current + 1).min(len - 1),
+            (false, true) => (current + len - 1) % len,
+            (false, false) => current.saturating_sub(1),
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Touch and mouse gestures, recognized from pointer events.
+//!
+//! Import [EmitGestureEvent] (it is also in the [prelude_traits][crate::prelude_traits])
+//! and call its methods on any element to get a Future+Stream of gestures.
+//!
+//! ```
+//! # use async_ui_web::{html::Div, prelude_traits::*};
+//! # use futures_lite::StreamExt;
+//! # let _ = async {
+//! let card = Div::new();
+//! let mut swipes = card.until_swipe();
+//! while let Some(swipe) = swipes.next().await {
+//!     // dismiss the card, go to the next page, ...
+//! #   let _ = swipe.direction;
+//! }
+//! # };
+//! ```
+//!
+//! The pointer is captured when it goes down on the element, so a drag keeps
+//! being tracked even if it leaves the element.
+//!
+//! Browsers scroll and zoom the page on touch by default, and cancel the pointer
+//! when they do. For pan, swipe, and pinch, give the element a suitable
+//! [`touch-action`](https://developer.mozilla.org/en-US/docs/Web/CSS/touch-action)
+//! style, such as `touch-action: none`.
+
+mod recognizers;
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use async_ui_internal_utils::dummy_waker::dummy_waker;
+use async_ui_web_html::events::EmitElementEvent;
+use futures_lite::Stream;
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+use web_sys::{Element, PointerEvent};
+
+use crate::timer::{self, Sleep};
+use recognizers::{
+    DoubleTapRecognizer, LongPressRecognizer, PanRecognizer, PinchRecognizer, Recognizer, Sample,
+    SampleKind, SwipeRecognizer, TapRecognizer,
+};
+
+/// A quick press and release without moving.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tap {
+    /// Where the pointer was released, in client coordinates.
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Two taps in quick succession at about the same place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DoubleTap {
+    /// Where the second tap was, in client coordinates.
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A press held for half a second without moving.
+/// It fires while the pointer is still down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LongPress {
+    /// Where the pointer went down, in client coordinates.
+    pub x: f64,
+    pub y: f64,
+}
+
+/// The stage of a continuous gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GesturePhase {
+    /// The first event of the gesture.
+    Start,
+    /// The gesture is ongoing.
+    Move,
+    /// The pointer was released. This is the last event of the gesture.
+    End,
+    /// The gesture was interrupted, by the browser or by another finger.
+    /// This is the last event of the gesture.
+    Cancel,
+}
+
+/// A single pointer dragging.
+///
+/// Velocities are in CSS pixels per millisecond, measured over the last 100 ms.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pan {
+    pub phase: GesturePhase,
+    /// The current position, in client coordinates.
+    pub x: f64,
+    pub y: f64,
+    /// Movement since the previous event of this gesture.
+    pub dx: f64,
+    pub dy: f64,
+    /// Movement since the pointer went down.
+    pub total_dx: f64,
+    pub total_dy: f64,
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+}
+
+/// The direction of a [Swipe].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// A quick flick in one direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Swipe {
+    pub direction: SwipeDirection,
+    /// The speed at release, in CSS pixels per millisecond.
+    pub velocity: f64,
+}
+
+/// Two pointers moving relative to each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pinch {
+    pub phase: GesturePhase,
+    /// The distance between the pointers, relative to when the gesture started.
+    pub scale: f64,
+    /// How far the line between the pointers has turned since the gesture started,
+    /// in radians, clockwise on screen.
+    pub rotation: f64,
+    /// The midpoint between the pointers, in client coordinates.
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
+/// Subscribe to gestures performed on an element.
+///
+/// Each method returns an independent Future+Stream; see the [module documentation][self].
+pub trait EmitGestureEvent: EmitElementEvent {
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    /// Taps (or clicks) on the element.
+    fn until_tap(&self) -> GestureStream<Tap> {
+        GestureStream::new(self.as_ref(), TapRecognizer::default())
+    }
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    /// Double taps on the element.
+    /// The first tap of each pair also shows up in [until_tap][Self::until_tap].
+    fn until_double_tap(&self) -> GestureStream<DoubleTap> {
+        GestureStream::new(self.as_ref(), DoubleTapRecognizer::default())
+    }
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    /// Long presses on the element.
+    fn until_long_press(&self) -> GestureStream<LongPress> {
+        GestureStream::new(self.as_ref(), LongPressRecognizer::default())
+    }
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    /// Single-pointer drags starting on the element.
+    fn until_pan(&self) -> GestureStream<Pan> {
+        GestureStream::new(self.as_ref(), PanRecognizer::default())
+    }
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    /// Swipes starting on the element. Each swipe is reported when the pointer is released.
+    fn until_swipe(&self) -> GestureStream<Swipe> {
+        GestureStream::new(self.as_ref(), SwipeRecognizer::default())
+    }
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    /// Two-finger pinch and rotate gestures on the element.
+    fn until_pinch(&self) -> GestureStream<Pinch> {
+        GestureStream::new(self.as_ref(), PinchRecognizer::default())
+    }
+}
+
+impl<T: EmitElementEvent + ?Sized> EmitGestureEvent for T {}
+
+const POINTER_EVENTS: [&str; 4] = ["pointerdown", "pointermove", "pointerup", "pointercancel"];
+
+struct Shared {
+    queue: VecDeque<PointerEvent>,
+    waker: Waker,
+}
+
+/// Future+Stream of gestures, returned by the methods of [EmitGestureEvent].
+///
+/// Listening starts when it is first polled, and stops when it is dropped.
+/// As a Future, it completes with the next gesture.
+pub struct GestureStream<G> {
+    element: Element,
+    recognizer: Box<dyn Recognizer<Output = G>>,
+    listener: Option<Closure<dyn Fn(PointerEvent)>>,
+    shared: Rc<RefCell<Shared>>,
+    output: VecDeque<G>,
+    timer: Option<(f64, Sleep)>,
+}
+
+impl<G> GestureStream<G> {
+    fn new(element: &Element, recognizer: impl Recognizer<Output = G> + 'static) -> Self {
+        Self {
+            element: element.clone(),
+            recognizer: Box::new(recognizer),
+            listener: None,
+            shared: Rc::new(RefCell::new(Shared {
+                queue: VecDeque::new(),
+                waker: dummy_waker(),
+            })),
+            output: VecDeque::new(),
+            timer: None,
+        }
+    }
+    fn register(&mut self) {
+        let shared = self.shared.clone();
+        let closure = Closure::new(move |ev: PointerEvent| {
+            let mut shared = shared.borrow_mut();
+            shared.queue.push_back(ev);
+            shared.waker.wake_by_ref();
+            drop(shared);
+            async_ui_web_core::executor::run_now();
+        });
+        for name in POINTER_EVENTS {
+            self.element
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .unwrap_throw();
+        }
+        self.listener = Some(closure);
+    }
+    fn process(&mut self) {
+        let events: Vec<_> = self.shared.borrow_mut().queue.drain(..).collect();
+        for ev in events {
+            let kind = match &*ev.type_() {
+                "pointerdown" => {
+                    // only the main mouse button
+                    if ev.pointer_type() == "mouse" && ev.button() != 0 {
+                        continue;
+                    }
+                    self.element.set_pointer_capture(ev.pointer_id()).ok();
+                    SampleKind::Down
+                }
+                "pointermove" => SampleKind::Move,
+                "pointerup" => SampleKind::Up,
+                _ => SampleKind::Cancel,
+            };
+            let sample = Sample {
+                id: ev.pointer_id(),
+                kind,
+                x: ev.client_x() as f64,
+                y: ev.client_y() as f64,
+                time: ev.time_stamp(),
+            };
+            self.recognizer.feed(&sample, &mut self.output);
+        }
+    }
+}
+
+// nothing is ever pinned structurally
+impl<G> Unpin for GestureStream<G> {}
+
+impl<G> Stream for GestureStream<G> {
+    type Item = G;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.listener.is_none() {
+            this.register();
+        }
+        {
+            let waker = &mut this.shared.borrow_mut().waker;
+            if !waker.will_wake(cx.waker()) {
+                *waker = cx.waker().to_owned();
+            }
+        }
+        this.process();
+        loop {
+            if let Some(gesture) = this.output.pop_front() {
+                return Poll::Ready(Some(gesture));
+            }
+            let Some(deadline) = this.recognizer.deadline() else {
+                this.timer = None;
+                return Poll::Pending;
+            };
+            if this.timer.as_ref().map(|t| t.0) != Some(deadline) {
+                let delay = (deadline - timer::now()).max(0.0);
+                this.timer = Some((
+                    deadline,
+                    timer::sleep(std::time::Duration::from_secs_f64(delay / 1000.0)),
+                ));
+            }
+            let (_, sleep) = this.timer.as_mut().unwrap();
+            match Pin::new(sleep).poll(cx) {
+                Poll::Ready(()) => {
+                    this.timer = None;
+                    // setTimeout rounds, so the clock may read slightly before the deadline
+                    let now = timer::now().max(deadline);
+                    this.recognizer.on_timer(now, &mut this.output);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<G> Future for GestureStream<G> {
+    type Output = G;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(Some(gesture)) => Poll::Ready(gesture),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<G> Drop for GestureStream<G> {
+    fn drop(&mut self) {
+        if let Some(closure) = self.listener.take() {
+            for name in POINTER_EVENTS {
+                self.element
+                    .remove_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                    .ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,709 @@
+//! Gesture state machines, fed with pointer samples.
+//! They know nothing about the DOM, so they can be tested natively.
+
+use std::{
+    collections::VecDeque,
+    f64::consts::{PI, TAU},
+};
+
+use super::{DoubleTap, GesturePhase, LongPress, Pan, Pinch, Swipe, SwipeDirection, Tap};
+
+/// How far (in CSS pixels) a pointer may wander and still count as a tap or press.
+const SLOP: f64 = 10.0;
+/// The longest a tap may be held down (in milliseconds).
+const TAP_MAX_DURATION: f64 = 500.0;
+/// The longest gap between the taps of a double tap.
+const DOUBLE_TAP_INTERVAL: f64 = 300.0;
+/// How close the taps of a double tap must be.
+const DOUBLE_TAP_DISTANCE: f64 = 30.0;
+/// How long the pointer must be held for a long press.
+const LONG_PRESS_DURATION: f64 = 500.0;
+/// How far the pointer must move before a pan starts.
+const PAN_THRESHOLD: f64 = 5.0;
+/// How far back we look when computing velocity.
+const VELOCITY_WINDOW: f64 = 100.0;
+/// The minimum distance and speed (in pixels per millisecond) of a swipe.
+const SWIPE_MIN_DISTANCE: f64 = 30.0;
+const SWIPE_MIN_VELOCITY: f64 = 0.3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SampleKind {
+    Down,
+    Move,
+    Up,
+    Cancel,
+}
+
+/// One pointer event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Sample {
+    pub(crate) id: i32,
+    pub(crate) kind: SampleKind,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    /// In milliseconds.
+    pub(crate) time: f64,
+}
+
+pub(crate) trait Recognizer {
+    type Output;
+    fn feed(&mut self, sample: &Sample, out: &mut VecDeque<Self::Output>);
+    /// When (if ever) [on_timer][Self::on_timer] should be called.
+    fn deadline(&self) -> Option<f64> {
+        None
+    }
+    fn on_timer(&mut self, _now: f64, _out: &mut VecDeque<Self::Output>) {}
+}
+
+/// The pointers currently down.
+#[derive(Default)]
+struct Pointers(Vec<(i32, f64, f64)>);
+
+impl Pointers {
+    fn update(&mut self, s: &Sample) {
+        match s.kind {
+            SampleKind::Down => {
+                self.0.retain(|p| p.0 != s.id);
+                self.0.push((s.id, s.x, s.y));
+            }
+            SampleKind::Move => {
+                if let Some(p) = self.0.iter_mut().find(|p| p.0 == s.id) {
+                    (p.1, p.2) = (s.x, s.y);
+                }
+            }
+            SampleKind::Up | SampleKind::Cancel => self.0.retain(|p| p.0 != s.id),
+        }
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+fn distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    (x2 - x1).hypot(y2 - y1)
+}
+
+/// A single pointer that went down, if it is still a candidate for a tap or press.
+#[derive(Clone, Copy)]
+struct Press {
+    id: i32,
+    x: f64,
+    y: f64,
+    time: f64,
+}
+
+impl Press {
+    /// Track the press through a sample; returns false if the press is no longer valid.
+    fn follow(&self, s: &Sample) -> bool {
+        s.id != self.id || distance(self.x, self.y, s.x, s.y) <= SLOP
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TapRecognizer {
+    pointers: Pointers,
+    press: Option<Press>,
+}
+
+impl TapRecognizer {
+    /// Feed a sample; returns the tap and its time if one completed.
+    fn feed_tap(&mut self, s: &Sample) -> Option<(Tap, f64)> {
+        let was_empty = self.pointers.len() == 0;
+        self.pointers.update(s);
+        let mut tap = None;
+        self.press = match (s.kind, self.press) {
+            (SampleKind::Down, _) if was_empty => Some(Press {
+                id: s.id,
+                x: s.x,
+                y: s.y,
+                time: s.time,
+            }),
+            // a second finger: not a tap
+            (SampleKind::Down, _) => None,
+            (SampleKind::Move, Some(press)) => press.follow(s).then_some(press),
+            (SampleKind::Up, Some(press)) if press.id == s.id => {
+                if press.follow(s) && s.time - press.time <= TAP_MAX_DURATION {
+                    tap = Some((Tap { x: s.x, y: s.y }, s.time));
+                }
+                None
+            }
+            (SampleKind::Cancel, Some(press)) if press.id == s.id => None,
+            (_, press) => press,
+        };
+        tap
+    }
+}
+
+impl Recognizer for TapRecognizer {
+    type Output = Tap;
+    fn feed(&mut self, s: &Sample, out: &mut VecDeque<Tap>) {
+        out.extend(self.feed_tap(s).map(|(tap, _)| tap));
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct DoubleTapRecognizer {
+    taps: TapRecognizer,
+    last: Option<(Tap, f64)>,
+}
+
+impl Recognizer for DoubleTapRecognizer {
+    type Output = DoubleTap;
+    fn feed(&mut self, s: &Sample, out: &mut VecDeque<DoubleTap>) {
+        let Some((tap, time)) = self.taps.feed_tap(s) else {
+            return;
+        };
+        match self.last.take() {
+            Some((last, last_time))
+                if time - last_time <= DOUBLE_TAP_INTERVAL
+                    && distance(last.x, last.y, tap.x, tap.y) <= DOUBLE_TAP_DISTANCE =>
+            {
+                out.push_back(DoubleTap { x: tap.x, y: tap.y });
+            }
+            _ => self.last = Some((tap, time)),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LongPressRecognizer {
+    pointers: Pointers,
+    press: Option<Press>,
+}
+
+impl Recognizer for LongPressRecognizer {
+    type Output = LongPress;
+    fn feed(&mut self, s: &Sample, _out: &mut VecDeque<LongPress>) {
+        let was_empty = self.pointers.len() == 0;
+        self.pointers.update(s);
+        self.press = match (s.kind, self.press) {
+            (SampleKind::Down, _) if was_empty => Some(Press {
+                id: s.id,
+                x: s.x,
+                y: s.y,
+                time: s.time,
+            }),
+            (SampleKind::Down, _) => None,
+            (SampleKind::Move, Some(press)) => press.follow(s).then_some(press),
+            (SampleKind::Up | SampleKind::Cancel, Some(press)) if press.id == s.id => None,
+            (_, press) => press,
+        };
+    }
+    fn deadline(&self) -> Option<f64> {
+        self.press.map(|press| press.time + LONG_PRESS_DURATION)
+    }
+    fn on_timer(&mut self, now: f64, out: &mut VecDeque<LongPress>) {
+        if let Some(press) = self.press {
+            if now >= press.time + LONG_PRESS_DURATION {
+                out.push_back(LongPress {
+                    x: press.x,
+                    y: press.y,
+                });
+                self.press = None;
+            }
+        }
+    }
+}
+
+enum PanState {
+    Idle,
+    /// Down, but hasn't moved far enough yet.
+    Pending(Press),
+    Panning {
+        id: i32,
+        start: (f64, f64),
+        last: (f64, f64),
+        /// Recent positions, for velocity.
+        history: VecDeque<(f64, f64, f64)>,
+    },
+}
+
+pub(crate) struct PanRecognizer {
+    pointers: Pointers,
+    state: PanState,
+}
+
+impl Default for PanRecognizer {
+    fn default() -> Self {
+        Self {
+            pointers: Pointers::default(),
+            state: PanState::Idle,
+        }
+    }
+}
+
+impl Recognizer for PanRecognizer {
+    type Output = Pan;
+    fn feed(&mut self, s: &Sample, out: &mut VecDeque<Pan>) {
+        let was_empty = self.pointers.len() == 0;
+        self.pointers.update(s);
+        let state = std::mem::replace(&mut self.state, PanState::Idle);
+        self.state = match (s.kind, state) {
+            (SampleKind::Down, _) if was_empty => PanState::Pending(Press {
+                id: s.id,
+                x: s.x,
+                y: s.y,
+                time: s.time,
+            }),
+            // a second finger turns this into some other gesture;
+            // the pan ends where its own pointer last was
+            (SampleKind::Down, PanState::Panning { start, last, .. }) => {
+                out.push_back(pan_event(
+                    GesturePhase::Cancel,
+                    start,
+                    last,
+                    last,
+                    (0.0, 0.0),
+                ));
+                PanState::Idle
+            }
+            (SampleKind::Down, _) => PanState::Idle,
+            (SampleKind::Move, PanState::Pending(press)) if press.id == s.id => {
+                if distance(press.x, press.y, s.x, s.y) > PAN_THRESHOLD {
+                    let start = (press.x, press.y);
+                    let at = (s.x, s.y);
+                    out.push_back(pan_event(GesturePhase::Start, start, start, at, (0.0, 0.0)));
+                    PanState::Panning {
+                        id: s.id,
+                        start,
+                        last: (s.x, s.y),
+                        history: VecDeque::from([
+                            (press.x, press.y, press.time),
+                            (s.x, s.y, s.time),
+                        ]),
+                    }
+                } else {
+                    PanState::Pending(press)
+                }
+            }
+            (
+                kind @ (SampleKind::Move | SampleKind::Up | SampleKind::Cancel),
+                PanState::Panning {
+                    id,
+                    start,
+                    last,
+                    mut history,
+                },
+            ) if id == s.id => {
+                history.push_back((s.x, s.y, s.time));
+                while history.len() > 2 && s.time - history[0].2 > VELOCITY_WINDOW {
+                    history.pop_front();
+                }
+                let velocity = velocity(&history);
+                let phase = match kind {
+                    SampleKind::Move => GesturePhase::Move,
+                    SampleKind::Up => GesturePhase::End,
+                    _ => GesturePhase::Cancel,
+                };
+                out.push_back(pan_event(phase, start, last, (s.x, s.y), velocity));
+                if kind == SampleKind::Move {
+                    PanState::Panning {
+                        id,
+                        start,
+                        last: (s.x, s.y),
+                        history,
+                    }
+                } else {
+                    PanState::Idle
+                }
+            }
+            (SampleKind::Up | SampleKind::Cancel, PanState::Pending(press)) if press.id == s.id => {
+                PanState::Idle
+            }
+            (_, state) => state,
+        };
+    }
+}
+
+/// A pan event at position `at`, having moved from `last`.
+fn pan_event(
+    phase: GesturePhase,
+    start: (f64, f64),
+    last: (f64, f64),
+    at: (f64, f64),
+    velocity: (f64, f64),
+) -> Pan {
+    Pan {
+        phase,
+        x: at.0,
+        y: at.1,
+        dx: at.0 - last.0,
+        dy: at.1 - last.1,
+        total_dx: at.0 - start.0,
+        total_dy: at.1 - start.1,
+        velocity_x: velocity.0,
+        velocity_y: velocity.1,
+    }
+}
+
+/// Velocity (pixels per millisecond) over the recorded positions.
+fn velocity(history: &VecDeque<(f64, f64, f64)>) -> (f64, f64) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else {
+        return (0.0, 0.0);
+    };
+    let dt = last.2 - first.2;
+    if dt <= 0.0 {
+        (0.0, 0.0)
+    } else {
+        ((last.0 - first.0) / dt, (last.1 - first.1) / dt)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SwipeRecognizer {
+    pan: PanRecognizer,
+    pans: VecDeque<Pan>,
+}
+
+impl Recognizer for SwipeRecognizer {
+    type Output = Swipe;
+    fn feed(&mut self, s: &Sample, out: &mut VecDeque<Swipe>) {
+        self.pan.feed(s, &mut self.pans);
+        for pan in self.pans.drain(..) {
+            if pan.phase != GesturePhase::End {
+                continue;
+            }
+            let horizontal = pan.total_dx.abs() >= pan.total_dy.abs();
+            let (distance, velocity) = if horizontal {
+                (pan.total_dx, pan.velocity_x)
+            } else {
+                (pan.total_dy, pan.velocity_y)
+            };
+            // the release must be fast, in the same direction as the whole movement
+            if distance.abs() < SWIPE_MIN_DISTANCE
+                || velocity.abs() < SWIPE_MIN_VELOCITY
+                || velocity.signum() != distance.signum()
+            {
+                continue;
+            }
+            let direction = match (horizontal, distance > 0.0) {
+                (true, true) => SwipeDirection::Right,
+                (true, false) => SwipeDirection::Left,
+                (false, true) => SwipeDirection::Down,
+                (false, false) => SwipeDirection::Up,
+            };
+            out.push_back(Swipe {
+                direction,
+                velocity: velocity.abs(),
+            });
+        }
+    }
+}
+
+struct PinchStart {
+    ids: (i32, i32),
+    distance: f64,
+    last_angle: f64,
+    rotation: f64,
+}
+
+#[derive(Default)]
+pub(crate) struct PinchRecognizer {
+    pointers: Pointers,
+    pinch: Option<PinchStart>,
+}
+
+impl PinchRecognizer {
+    fn event(&mut self, phase: GesturePhase) -> Option<Pinch> {
+        let pinch = self.pinch.as_mut()?;
+        let find = |id| self.pointers.0.iter().find(|p| p.0 == id).copied();
+        let (a, b) = (find(pinch.ids.0)?, find(pinch.ids.1)?);
+        let angle = (b.2 - a.2).atan2(b.1 - a.1);
+        // accumulate the change, so the rotation can go past half a turn
+        let mut delta = (angle - pinch.last_angle) % TAU;
+        if delta > PI {
+            delta -= TAU;
+        } else if delta < -PI {
+            delta += TAU;
+        }
+        pinch.rotation += delta;
+        pinch.last_angle = angle;
+        let d = distance(a.1, a.2, b.1, b.2);
+        Some(Pinch {
+            phase,
+            scale: if pinch.distance > 0.0 {
+                d / pinch.distance
+            } else {
+                1.0
+            },
+            rotation: pinch.rotation,
+            center_x: (a.1 + b.1) / 2.0,
+            center_y: (a.2 + b.2) / 2.0,
+        })
+    }
+}
+
+impl Recognizer for PinchRecognizer {
+    type Output = Pinch;
+    fn feed(&mut self, s: &Sample, out: &mut VecDeque<Pinch>) {
+        let involved = self
+            .pinch
+            .as_ref()
+            .is_some_and(|p| p.ids.0 == s.id || p.ids.1 == s.id);
+        match s.kind {
+            SampleKind::Down => {
+                self.pointers.update(s);
+                if self.pinch.is_none() && self.pointers.len() == 2 {
+                    let (a, b) = (self.pointers.0[0], self.pointers.0[1]);
+                    self.pinch = Some(PinchStart {
+                        ids: (a.0, b.0),
+                        distance: distance(a.1, a.2, b.1, b.2),
+                        last_angle: (b.2 - a.2).atan2(b.1 - a.1),
+                        rotation: 0.0,
+                    });
+                    out.extend(self.event(GesturePhase::Start));
+                }
+            }
+            SampleKind::Move => {
+                self.pointers.update(s);
+                if involved {
+                    out.extend(self.event(GesturePhase::Move));
+                }
+            }
+            SampleKind::Up | SampleKind::Cancel => {
+                if involved {
+                    // report with the position of the lifted pointer, then forget it
+                    self.pointers.update(&Sample {
+                        kind: SampleKind::Move,
+                        ..*s
+                    });
+                    let phase = if s.kind == SampleKind::Up {
+                        GesturePhase::End
+                    } else {
+                        GesturePhase::Cancel
+                    };
+                    out.extend(self.event(phase));
+                    self.pinch = None;
+                }
+                self.pointers.update(s);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(id: i32, kind: SampleKind, x: f64, y: f64, time: f64) -> Sample {
+        Sample {
+            id,
+            kind,
+            x,
+            y,
+            time,
+        }
+    }
+    use SampleKind::*;
+
+    fn run<R: Recognizer>(r: &mut R, samples: &[Sample]) -> Vec<R::Output> {
+        let mut out = VecDeque::new();
+        for s in samples {
+            r.feed(s, &mut out);
+        }
+        out.into()
+    }
+
+    #[test]
+    fn tap() {
+        let mut r = TapRecognizer::default();
+        let taps = run(
+            &mut r,
+            &[
+                sample(1, Down, 10.0, 10.0, 0.0),
+                sample(1, Move, 12.0, 11.0, 50.0),
+                sample(1, Up, 12.0, 11.0, 100.0),
+            ],
+        );
+        assert_eq!(taps, [Tap { x: 12.0, y: 11.0 }]);
+        // moved too far
+        let taps = run(
+            &mut r,
+            &[
+                sample(1, Down, 10.0, 10.0, 0.0),
+                sample(1, Move, 40.0, 10.0, 50.0),
+                sample(1, Up, 10.0, 10.0, 100.0),
+            ],
+        );
+        assert!(taps.is_empty());
+        // held too long
+        let taps = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(1, Up, 0.0, 0.0, 900.0),
+            ],
+        );
+        assert!(taps.is_empty());
+        // two fingers
+        let taps = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(2, Down, 50.0, 0.0, 10.0),
+                sample(1, Up, 0.0, 0.0, 50.0),
+                sample(2, Up, 50.0, 0.0, 60.0),
+            ],
+        );
+        assert!(taps.is_empty());
+    }
+
+    #[test]
+    fn double_tap() {
+        let mut r = DoubleTapRecognizer::default();
+        let taps = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(1, Up, 0.0, 0.0, 50.0),
+                sample(2, Down, 5.0, 5.0, 200.0),
+                sample(2, Up, 5.0, 5.0, 250.0),
+                // a third tap starts over
+                sample(3, Down, 5.0, 5.0, 300.0),
+                sample(3, Up, 5.0, 5.0, 350.0),
+            ],
+        );
+        assert_eq!(taps, [DoubleTap { x: 5.0, y: 5.0 }]);
+        // too slow
+        let mut r = DoubleTapRecognizer::default();
+        let taps = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(1, Up, 0.0, 0.0, 50.0),
+                sample(2, Down, 0.0, 0.0, 500.0),
+                sample(2, Up, 0.0, 0.0, 550.0),
+            ],
+        );
+        assert!(taps.is_empty());
+    }
+
+    #[test]
+    fn long_press() {
+        let mut r = LongPressRecognizer::default();
+        let mut out = VecDeque::new();
+        r.feed(&sample(1, Down, 3.0, 4.0, 1000.0), &mut out);
+        assert_eq!(r.deadline(), Some(1500.0));
+        r.on_timer(1400.0, &mut out);
+        assert!(out.is_empty());
+        r.on_timer(1500.0, &mut out);
+        assert_eq!(out, [LongPress { x: 3.0, y: 4.0 }]);
+        assert_eq!(r.deadline(), None);
+
+        // released early
+        let mut r = LongPressRecognizer::default();
+        r.feed(&sample(1, Down, 0.0, 0.0, 0.0), &mut out);
+        r.feed(&sample(1, Up, 0.0, 0.0, 100.0), &mut out);
+        assert_eq!(r.deadline(), None);
+    }
+
+    #[test]
+    fn pan() {
+        let mut r = PanRecognizer::default();
+        let pans = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(1, Move, 2.0, 0.0, 10.0),
+                sample(1, Move, 10.0, 0.0, 20.0),
+                sample(1, Move, 20.0, 5.0, 30.0),
+                sample(1, Up, 20.0, 5.0, 40.0),
+            ],
+        );
+        let phases: Vec<_> = pans.iter().map(|p| p.phase).collect();
+        assert_eq!(
+            phases,
+            [GesturePhase::Start, GesturePhase::Move, GesturePhase::End]
+        );
+        assert_eq!((pans[0].total_dx, pans[0].dx), (10.0, 10.0));
+        assert_eq!((pans[1].dx, pans[1].dy), (10.0, 5.0));
+        assert_eq!((pans[1].total_dx, pans[1].total_dy), (20.0, 5.0));
+        assert!(pans[2].velocity_x > 0.0);
+    }
+
+    #[test]
+    fn pan_cancelled_by_second_pointer() {
+        let mut r = PanRecognizer::default();
+        let pans = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(1, Move, 10.0, 0.0, 10.0),
+                sample(1, Move, 20.0, 5.0, 20.0),
+                sample(2, Down, 300.0, 200.0, 30.0),
+                sample(1, Move, 30.0, 5.0, 40.0),
+            ],
+        );
+        let phases: Vec<_> = pans.iter().map(|p| p.phase).collect();
+        assert_eq!(
+            phases,
+            [
+                GesturePhase::Start,
+                GesturePhase::Move,
+                GesturePhase::Cancel
+            ]
+        );
+        // the cancel is where the panning pointer was, not the new one
+        let cancel = &pans[2];
+        assert_eq!((cancel.x, cancel.y), (20.0, 5.0));
+        assert_eq!((cancel.dx, cancel.dy), (0.0, 0.0));
+        assert_eq!((cancel.total_dx, cancel.total_dy), (20.0, 5.0));
+    }
+
+    #[test]
+    fn swipe() {
+        let mut r = SwipeRecognizer::default();
+        let swipes = run(
+            &mut r,
+            &[
+                sample(1, Down, 100.0, 0.0, 0.0),
+                sample(1, Move, 80.0, 2.0, 16.0),
+                sample(1, Move, 50.0, 3.0, 32.0),
+                sample(1, Up, 20.0, 3.0, 48.0),
+            ],
+        );
+        assert_eq!(swipes.len(), 1);
+        assert_eq!(swipes[0].direction, SwipeDirection::Left);
+        // a slow drag is not a swipe
+        let swipes = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(1, Move, 0.0, 50.0, 500.0),
+                sample(1, Move, 0.0, 100.0, 1000.0),
+                sample(1, Up, 0.0, 100.0, 1500.0),
+            ],
+        );
+        assert!(swipes.is_empty());
+    }
+
+    #[test]
+    fn pinch() {
+        let mut r = PinchRecognizer::default();
+        let pinches = run(
+            &mut r,
+            &[
+                sample(1, Down, 0.0, 0.0, 0.0),
+                sample(2, Down, 100.0, 0.0, 10.0),
+                sample(2, Move, 200.0, 0.0, 20.0),
+                // rotate a quarter turn around pointer 1
+                sample(2, Move, 0.0, 200.0, 30.0),
+                sample(1, Up, 0.0, 0.0, 40.0),
+            ],
+        );
+        let phases: Vec<_> = pinches.iter().map(|p| p.phase).collect();
+        assert_eq!(
+            phases,
+            [
+                GesturePhase::Start,
+                GesturePhase::Move,
+                GesturePhase::Move,
+                GesturePhase::End
+            ]
+        );
+        assert_eq!(pinches[0].scale, 1.0);
+        assert_eq!(pinches[1].scale, 2.0);
+        assert_eq!((pinches[1].center_x, pinches[1].center_y), (100.0, 0.0));
+        assert!((pinches[2].rotation - PI / 2.0).abs() < 1e-9);
+    }
+}
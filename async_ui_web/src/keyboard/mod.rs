@@ -0,0 +1,433 @@
+//! Keyboard shortcuts.
+//!
+//! ```
+//! # use async_ui_web::{keyboard::{shortcut, ShortcutScope}, race};
+//! # use futures_lite::StreamExt;
+//! # let _ = async {
+//! // a page-wide shortcut
+//! let mut save = shortcut("Mod+S");
+//! while let Some(_ev) = save.next().await {
+//!     // save
+//! }
+//!
+//! // while a modal is open, its shortcuts shadow everything else
+//! let modal_scope = ShortcutScope::new_modal();
+//! race((
+//!     modal_scope.bind("Escape"),
+//!     modal_scope.bind("Ctrl+Enter"),
+//! ))
+//! .await;
+//! # };
+//! ```
+//!
+//! Shortcuts are written as strings such as `"Ctrl+Shift+K"` or chords such
+//! as `"g i"`. See [Shortcut] for the syntax.
+//!
+//! Every binding belongs to a [ShortcutScope]. Scopes form a stack: the most
+//! recently created scope is on top, and gets the first chance to handle each
+//! key press. A *modal* scope shadows every scope beneath it, so while a
+//! dialog is open, the page's shortcuts don't fire.
+//! Bindings made with [shortcut] belong to the page scope,
+//! which is always at the bottom of the stack.
+//!
+//! A binding is active while its [ShortcutStream] is alive (and has been polled once);
+//! a scope is on the stack while its [ShortcutScope] is alive.
+//! When a shortcut fires, the browser's default action for the key press is prevented.
+//!
+//! While the user is typing in a text field, shortcuts whose keys would edit
+//! the text (characters, Backspace, Enter, arrow keys, and so on, without Ctrl,
+//! Alt, or Meta) don't fire. So binding `"g i"` doesn't stop anyone from typing
+//! "gi", but `"Escape"` and `"Ctrl+S"` still work.
+
+mod spec;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+use async_ui_internal_utils::dummy_waker::dummy_waker;
+use async_ui_web_core::window::{DOCUMENT, WINDOW};
+use futures_lite::Stream;
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+use web_sys::{HtmlElement, KeyboardEvent};
+
+use spec::{is_modifier_key, KeyCombo};
+pub use spec::{ParseShortcutError, Shortcut};
+
+/// How long (in milliseconds) to wait for the next key of a chord.
+const CHORD_TIMEOUT: f64 = 1000.0;
+
+/// The ID of the page scope, which is always at the bottom of the stack.
+const PAGE_SCOPE: u64 = 0;
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry {
+        layers: vec![Layer { id: PAGE_SCOPE, modal: false, bindings: Vec::new() }],
+        chord: Vec::new(),
+        chord_time: 0.0,
+        listener: None,
+    });
+    static NEXT_ID: Cell<u64> = const { Cell::new(PAGE_SCOPE + 1) };
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.with(|c| {
+        let id = c.get();
+        c.set(id + 1);
+        id
+    })
+}
+
+/// Whether `Mod` means Meta (Cmd) rather than Ctrl.
+fn is_apple_platform() -> bool {
+    WINDOW.with(|window| {
+        let navigator = window.navigator();
+        let platform = navigator.platform().unwrap_or_default();
+        ["Mac", "iPhone", "iPad", "iPod"]
+            .iter()
+            .any(|p| platform.starts_with(p))
+    })
+}
+
+struct Registry {
+    /// Bottom to top.
+    layers: Vec<Layer>,
+    /// The keys of a chord in progress.
+    chord: Vec<KeyCombo>,
+    /// When the last key of the chord in progress was pressed.
+    chord_time: f64,
+    /// The document keydown listener. Once added, it is kept for the rest of the
+    /// page's life (removing it would mean dropping it from inside its own invocation).
+    listener: Option<Closure<dyn Fn(KeyboardEvent)>>,
+}
+
+struct Layer {
+    id: u64,
+    modal: bool,
+    /// Oldest first.
+    bindings: Vec<Binding>,
+}
+
+struct Binding {
+    id: u64,
+    shortcut: Shortcut,
+    shared: Weak<RefCell<Shared>>,
+}
+
+struct Shared {
+    queue: VecDeque<KeyboardEvent>,
+    waker: Waker,
+}
+
+/// What the bindings in reach say about a sequence of key presses.
+enum Lookup {
+    /// This binding's shortcut is the sequence.
+    Fire(Rc<RefCell<Shared>>),
+    /// Some binding's shortcut starts with the sequence.
+    /// Has that binding's combination for the key just pressed.
+    Prefix(KeyCombo),
+    None,
+}
+
+impl Registry {
+    fn ensure_listener(&mut self) {
+        if self.listener.is_some() {
+            return;
+        }
+        let listener = Closure::new(on_keydown);
+        DOCUMENT.with(|doc| {
+            doc.add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref())
+                .unwrap_throw()
+        });
+        self.listener = Some(listener);
+    }
+    /// Find which binding (if any) wants the given key sequence.
+    /// Scopes are searched top to bottom, stopping after the first modal scope.
+    /// Within a scope, the most recent binding wins.
+    fn lookup(&self, ev: &KeyboardEvent, sequence: &[KeyCombo], typing: bool) -> Lookup {
+        for layer in self.layers.iter().rev() {
+            for binding in layer.bindings.iter().rev() {
+                let combos = &binding.shortcut.combos;
+                if combos.len() < sequence.len() + 1
+                    || (typing && combos.iter().any(KeyCombo::edits_text))
+                    || combos[..sequence.len()] != *sequence
+                    || !combo_matches(&combos[sequence.len()], ev)
+                {
+                    continue;
+                }
+                if combos.len() > sequence.len() + 1 {
+                    return Lookup::Prefix(combos[sequence.len()].clone());
+                }
+                if let Some(shared) = binding.shared.upgrade() {
+                    return Lookup::Fire(shared);
+                }
+            }
+            if layer.modal {
+                break;
+            }
+        }
+        Lookup::None
+    }
+}
+
+/// Whether the key press matches the combination.
+fn combo_matches(combo: &KeyCombo, ev: &KeyboardEvent) -> bool {
+    if combo.ctrl != ev.ctrl_key()
+        || combo.alt != ev.alt_key()
+        || combo.meta != ev.meta_key()
+        || (combo.shift != ev.shift_key() && !combo.shift_implied())
+    {
+        return false;
+    }
+    if ev.key().to_lowercase() == combo.key {
+        return true;
+    }
+    // With Alt (Option on Mac) held, `key` is often a special character.
+    // Fall back to the physical key for letters and digits.
+    let code = ev.code();
+    let from_code = code
+        .strip_prefix("Key")
+        .or_else(|| code.strip_prefix("Digit"))
+        .map(str::to_lowercase);
+    from_code.is_some_and(|k| k == combo.key)
+}
+
+/// Whether the user is typing into the event's target.
+fn is_typing(ev: &KeyboardEvent) -> bool {
+    let Some(target) = ev.target().and_then(|t| t.dyn_into::<HtmlElement>().ok()) else {
+        return false;
+    };
+    if target.is_content_editable() {
+        return true;
+    }
+    match &*target.tag_name() {
+        "TEXTAREA" | "SELECT" => true,
+        "INPUT" => {
+            let ty = target.get_attribute("type").unwrap_or_default();
+            !matches!(
+                &*ty.to_ascii_lowercase(),
+                "button" | "checkbox" | "radio" | "range" | "reset" | "submit" | "color" | "file"
+            )
+        }
+        _ => false,
+    }
+}
+
+fn on_keydown(ev: KeyboardEvent) {
+    if ev.default_prevented() || ev.is_composing() || is_modifier_key(&ev.key().to_lowercase()) {
+        return;
+    }
+    let typing = is_typing(&ev);
+    let fired = REGISTRY.with(|reg| {
+        let reg = &mut *reg.borrow_mut();
+        let now = ev.time_stamp();
+        if now - reg.chord_time > CHORD_TIMEOUT {
+            reg.chord.clear();
+        }
+        let mut lookup = reg.lookup(&ev, &reg.chord, typing);
+        if matches!(lookup, Lookup::None) && !reg.chord.is_empty() {
+            // The chord in progress was abandoned; maybe this key starts a new one.
+            reg.chord.clear();
+            lookup = reg.lookup(&ev, &[], typing);
+        }
+        match lookup {
+            Lookup::Fire(shared) => {
+                reg.chord.clear();
+                let mut shared = shared.borrow_mut();
+                shared.queue.push_back(ev.clone());
+                shared.waker.wake_by_ref();
+                true
+            }
+            Lookup::Prefix(combo) => {
+                reg.chord.push(combo);
+                reg.chord_time = now;
+                true
+            }
+            Lookup::None => false,
+        }
+    });
+    if fired {
+        ev.prevent_default();
+        async_ui_web_core::executor::run_now();
+    }
+}
+
+/**
+A layer of keyboard shortcuts.
+
+Create a scope for each part of the UI that has its own shortcuts, such as a
+dialog or an editor. The scope is on the shortcut stack from when it is created
+until it is dropped. See the [module documentation][self] for how scopes interact.
+
+```
+# use async_ui_web::keyboard::ShortcutScope;
+# use futures_lite::StreamExt;
+# let _ = async {
+let scope = ShortcutScope::new();
+let mut next = scope.bind("j");
+while let Some(_ev) = next.next().await {
+    // go to next item
+}
+# };
+```
+ */
+pub struct ShortcutScope {
+    id: u64,
+}
+
+impl Default for ShortcutScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShortcutScope {
+    /// Create a scope on top of the stack. Scopes beneath it still get the key
+    /// presses this scope doesn't handle.
+    pub fn new() -> Self {
+        Self::new_with_modal(false)
+    }
+    /// Create a modal scope on top of the stack.
+    /// Scopes beneath it get no key presses until it is dropped.
+    pub fn new_modal() -> Self {
+        Self::new_with_modal(true)
+    }
+    fn new_with_modal(modal: bool) -> Self {
+        let id = next_id();
+        REGISTRY.with(|reg| {
+            reg.borrow_mut().layers.push(Layer {
+                id,
+                modal,
+                bindings: Vec::new(),
+            })
+        });
+        Self { id }
+    }
+    /// Listen for a shortcut in this scope.
+    ///
+    /// The returned object is both a [Future] (resolving at the next time the
+    /// shortcut is pressed) and a never-ending [Stream] (yielding every time).
+    /// The binding is removed when it is dropped.
+    ///
+    /// **Panics** if the shortcut can't be parsed.
+    /// Use [bind_parsed][Self::bind_parsed] to handle parse errors.
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    pub fn bind(&self, shortcut: &str) -> ShortcutStream {
+        self.bind_parsed(Shortcut::parse(shortcut).unwrap_throw())
+    }
+    /// Like [bind][Self::bind], but for an already-parsed shortcut.
+    #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+    pub fn bind_parsed(&self, shortcut: Shortcut) -> ShortcutStream {
+        ShortcutStream::new(self.id, shortcut)
+    }
+}
+
+impl Drop for ShortcutScope {
+    fn drop(&mut self) {
+        REGISTRY.with(|reg| reg.borrow_mut().layers.retain(|layer| layer.id != self.id));
+    }
+}
+
+/// Listen for a page-wide shortcut.
+///
+/// Like [ShortcutScope::bind], but in the page scope, which is at the bottom of the stack.
+///
+/// **Panics** if the shortcut can't be parsed.
+#[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+pub fn shortcut(shortcut: &str) -> ShortcutStream {
+    ShortcutStream::new(PAGE_SCOPE, Shortcut::parse(shortcut).unwrap_throw())
+}
+
+/// A struct implementing both [Future] and [Stream].
+/// Yields the [KeyboardEvent] of the last key press of the shortcut.
+///
+/// Use [shortcut] or [ShortcutScope::bind] to get this struct.
+pub struct ShortcutStream {
+    scope: u64,
+    /// Taken out when we register, on first poll.
+    shortcut: Option<Shortcut>,
+    id: u64,
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl ShortcutStream {
+    fn new(scope: u64, shortcut: Shortcut) -> Self {
+        Self {
+            scope,
+            shortcut: Some(shortcut),
+            id: next_id(),
+            shared: Rc::new(RefCell::new(Shared {
+                queue: VecDeque::new(),
+                waker: dummy_waker(),
+            })),
+        }
+    }
+}
+
+impl Future for ShortcutStream {
+    type Output = KeyboardEvent;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(Some(ev)) => Poll::Ready(ev),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl Stream for ShortcutStream {
+    type Item = KeyboardEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        {
+            let shared = &mut *this.shared.borrow_mut();
+            let waker = cx.waker();
+            if !shared.waker.will_wake(waker) {
+                shared.waker = waker.to_owned();
+            }
+        }
+        if let Some(shortcut) = this.shortcut.take() {
+            REGISTRY.with(|reg| {
+                let reg = &mut *reg.borrow_mut();
+                reg.ensure_listener();
+                // If the scope is already gone, the binding never fires.
+                if let Some(layer) = reg.layers.iter_mut().find(|l| l.id == this.scope) {
+                    layer.bindings.push(Binding {
+                        id: this.id,
+                        shortcut,
+                        shared: Rc::downgrade(&this.shared),
+                    });
+                }
+            });
+            Poll::Pending
+        } else if let Some(ev) = this.shared.borrow_mut().queue.pop_front() {
+            Poll::Ready(Some(ev))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for ShortcutStream {
+    fn drop(&mut self) {
+        if self.shortcut.is_some() {
+            return;
+        }
+        REGISTRY.with(|reg| {
+            if let Some(layer) = reg
+                .borrow_mut()
+                .layers
+                .iter_mut()
+                .find(|l| l.id == self.scope)
+            {
+                layer.bindings.retain(|b| b.id != self.id);
+            }
+        });
+    }
+}
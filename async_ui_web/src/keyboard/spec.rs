@@ -0,0 +1,364 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A parsed keyboard shortcut: one key combination, or a chord (sequence)
+/// of them such as `"g i"`.
+///
+/// Parse with [FromStr] (`"Ctrl+Shift+K".parse()`) or [Shortcut::parse].
+///
+/// ### Syntax
+/// *   A combination is modifiers and a key joined by `+`, such as `Ctrl+Shift+K`.
+///     Names are case-insensitive.
+/// *   A chord is combinations separated by spaces, such as `g i` or `Ctrl+K Ctrl+S`.
+///     Each combination must be pressed within a second of the previous one.
+/// *   Modifiers are `Ctrl` (or `Control`), `Shift`, `Alt` (or `Option`), and
+///     `Meta` (or `Cmd`, `Command`, `Super`, `Win`).
+///     `Mod` (or `CmdOrCtrl`) means `Meta` on Apple platforms and `Ctrl` elsewhere.
+/// *   The key is a [`KeyboardEvent.key`](https://developer.mozilla.org/en-US/docs/Web/API/UI_Events/Keyboard_event_key_values)
+///     value such as `K`, `Enter`, `ArrowUp`, or `?`.
+///     Some aliases are accepted: `Esc`, `Space`, `Plus`, `Up`/`Down`/`Left`/`Right`,
+///     `Del`, `Ins`, and `Return`.
+///
+/// A single printable character that isn't a letter (such as `?`) matches
+/// whether or not Shift is held, since the character itself already implies
+/// the Shift state; write `Shift` explicitly to require it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub(super) combos: Vec<KeyCombo>,
+}
+
+/// One key with its modifiers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct KeyCombo {
+    /// Lowercased `KeyboardEvent.key` value.
+    pub(super) key: String,
+    pub(super) ctrl: bool,
+    pub(super) shift: bool,
+    pub(super) alt: bool,
+    pub(super) meta: bool,
+}
+
+/// The error returned when a [Shortcut] can't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseShortcutError {
+    spec: String,
+    reason: &'static str,
+}
+
+impl Display for ParseShortcutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid shortcut {:?}: {}", self.spec, self.reason)
+    }
+}
+
+impl std::error::Error for ParseShortcutError {}
+
+impl FromStr for Shortcut {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Shortcut {
+    /// Parse a shortcut, resolving `Mod` for the platform the page is running on.
+    pub fn parse(spec: &str) -> Result<Self, ParseShortcutError> {
+        Self::parse_for(spec, super::is_apple_platform())
+    }
+    pub(super) fn parse_for(spec: &str, apple: bool) -> Result<Self, ParseShortcutError> {
+        let error = |reason| ParseShortcutError {
+            spec: spec.to_owned(),
+            reason,
+        };
+        let combos = spec
+            .split_whitespace()
+            .map(|token| KeyCombo::parse(token, apple).map_err(error))
+            .collect::<Result<Vec<_>, _>>()?;
+        if combos.is_empty() {
+            return Err(error("empty"));
+        }
+        Ok(Self { combos })
+    }
+}
+
+impl KeyCombo {
+    fn parse(token: &str, apple: bool) -> Result<Self, &'static str> {
+        // The key itself may be "+", as in "Ctrl++".
+        let (modifiers, key) = if token == "+" {
+            (None, "+")
+        } else if let Some(modifiers) = token.strip_suffix("++") {
+            (Some(modifiers), "+")
+        } else {
+            match token.rsplit_once('+') {
+                Some((modifiers, key)) => (Some(modifiers), key),
+                None => (None, token),
+            }
+        };
+        if key.is_empty() {
+            return Err("missing key");
+        }
+        let mut combo = Self {
+            key: normalize_key(key),
+            ctrl: false,
+            shift: false,
+            alt: false,
+            meta: false,
+        };
+        if is_modifier_key(&combo.key) {
+            return Err("the key must not be a modifier");
+        }
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            let flag = match &*modifier.to_ascii_lowercase() {
+                "ctrl" | "control" => &mut combo.ctrl,
+                "shift" => &mut combo.shift,
+                "alt" | "option" => &mut combo.alt,
+                "meta" | "cmd" | "command" | "super" | "win" => &mut combo.meta,
+                "mod" | "cmdorctrl" if apple => &mut combo.meta,
+                "mod" | "cmdorctrl" => &mut combo.ctrl,
+                "" => return Err("missing modifier"),
+                _ => return Err("unknown modifier"),
+            };
+            if *flag {
+                return Err("repeated modifier");
+            }
+            *flag = true;
+        }
+        Ok(combo)
+    }
+    /// Whether pressing this combination in a text field would edit the text.
+    pub(super) fn edits_text(&self) -> bool {
+        !(self.ctrl || self.alt || self.meta)
+            && (self.key.chars().nth(1).is_none()
+                || matches!(
+                    &*self.key,
+                    "backspace"
+                        | "delete"
+                        | "enter"
+                        | "tab"
+                        | "arrowleft"
+                        | "arrowright"
+                        | "arrowup"
+                        | "arrowdown"
+                        | "home"
+                        | "end"
+                        | "pageup"
+                        | "pagedown"
+                ))
+    }
+    /// Whether Shift may be in either state when matching this combination.
+    pub(super) fn shift_implied(&self) -> bool {
+        let mut chars = self.key.chars();
+        matches!((chars.next(), chars.next()), (Some(c), None) if !c.is_alphabetic() && c != ' ')
+            && !self.shift
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    let lower = key.to_lowercase();
+    match &*lower {
+        "esc" => "escape",
+        "space" | "spacebar" => " ",
+        "plus" => "+",
+        "up" => "arrowup",
+        "down" => "arrowdown",
+        "left" => "arrowleft",
+        "right" => "arrowright",
+        "del" => "delete",
+        "ins" => "insert",
+        "return" => "enter",
+        _ => return lower,
+    }
+    .to_owned()
+}
+
+/// Whether the (lowercased) key is a modifier key pressed on its own.
+pub(super) fn is_modifier_key(key: &str) -> bool {
+    matches!(
+        key,
+        "control" | "shift" | "alt" | "meta" | "altgraph" | "os" | "capslock" | "fn"
+    )
+}
+
+impl Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+            (self.meta, "Meta+"),
+        ] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        match &*self.key {
+            " " => f.write_str("Space"),
+            key => {
+                // Capitalize the first letter ("k" => "K", "enter" => "Enter"),
+                // except for keys whose standard names have more capitals.
+                let name = match key {
+                    "arrowup" => "ArrowUp",
+                    "arrowdown" => "ArrowDown",
+                    "arrowleft" => "ArrowLeft",
+                    "arrowright" => "ArrowRight",
+                    "pageup" => "PageUp",
+                    "pagedown" => "PageDown",
+                    _ => {
+                        let mut chars = key.chars();
+                        if let Some(first) = chars.next() {
+                            write!(f, "{}", first.to_uppercase())?;
+                        }
+                        return f.write_str(chars.as_str());
+                    }
+                };
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+/// Formats in the canonical `Ctrl+Alt+Shift+Meta+Key` form, with chords separated by spaces.
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, combo) in self.combos.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            combo.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(key: &str, ctrl: bool, shift: bool, alt: bool, meta: bool) -> KeyCombo {
+        KeyCombo {
+            key: key.into(),
+            ctrl,
+            shift,
+            alt,
+            meta,
+        }
+    }
+
+    #[test]
+    fn parse_combos() {
+        let parse = |s| Shortcut::parse_for(s, false).unwrap().combos;
+        assert_eq!(parse("k"), [combo("k", false, false, false, false)]);
+        assert_eq!(
+            parse("Ctrl+Shift+K"),
+            [combo("k", true, true, false, false)]
+        );
+        assert_eq!(
+            parse("shift+ctrl+k"),
+            [combo("k", true, true, false, false)]
+        );
+        assert_eq!(
+            parse("Alt+Enter"),
+            [combo("enter", false, false, true, false)]
+        );
+        assert_eq!(
+            parse("Cmd+Esc"),
+            [combo("escape", false, false, false, true)]
+        );
+        assert_eq!(parse("Ctrl++"), [combo("+", true, false, false, false)]);
+        assert_eq!(parse("+"), [combo("+", false, false, false, false)]);
+        assert_eq!(parse("Ctrl+Plus"), [combo("+", true, false, false, false)]);
+        assert_eq!(parse("Space"), [combo(" ", false, false, false, false)]);
+        assert_eq!(parse("up"), [combo("arrowup", false, false, false, false)]);
+    }
+
+    #[test]
+    fn parse_chords() {
+        let parse = |s| Shortcut::parse_for(s, false).unwrap().combos;
+        assert_eq!(
+            parse("g i"),
+            [
+                combo("g", false, false, false, false),
+                combo("i", false, false, false, false)
+            ]
+        );
+        assert_eq!(
+            parse("  Ctrl+K   Ctrl+S "),
+            [
+                combo("k", true, false, false, false),
+                combo("s", true, false, false, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn platform_modifier() {
+        assert_eq!(
+            Shortcut::parse_for("Mod+S", false).unwrap().combos,
+            [combo("s", true, false, false, false)]
+        );
+        assert_eq!(
+            Shortcut::parse_for("CmdOrCtrl+S", true).unwrap().combos,
+            [combo("s", false, false, false, true)]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for invalid in [
+            "",
+            "   ",
+            "Ctrl+",
+            "Ctrl+Ctrl+K",
+            "Hyper+K",
+            "Ctrl+Shift",
+            "Shift",
+            "+K",
+            "Ctrl++K",
+        ] {
+            assert!(
+                Shortcut::parse_for(invalid, false).is_err(),
+                "{invalid:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn implied_shift() {
+        let parse = |s| Shortcut::parse_for(s, false).unwrap().combos.remove(0);
+        assert!(parse("?").shift_implied());
+        assert!(parse("Ctrl+1").shift_implied());
+        assert!(!parse("k").shift_implied());
+        assert!(!parse("Shift+?").shift_implied());
+        assert!(!parse("Enter").shift_implied());
+        assert!(!parse("Space").shift_implied());
+    }
+
+    #[test]
+    fn edits_text() {
+        let parse = |s| Shortcut::parse_for(s, false).unwrap().combos.remove(0);
+        assert!(parse("g").edits_text());
+        assert!(parse("Shift+?").edits_text());
+        assert!(parse("Backspace").edits_text());
+        assert!(!parse("Escape").edits_text());
+        assert!(!parse("F2").edits_text());
+        assert!(!parse("Ctrl+S").edits_text());
+        assert!(!parse("Alt+ArrowUp").edits_text());
+    }
+
+    #[test]
+    fn display() {
+        for (spec, display) in [
+            ("ctrl+shift+k", "Ctrl+Shift+K"),
+            ("Meta+Alt+arrowup", "Alt+Meta+ArrowUp"),
+            ("g i", "G I"),
+            ("Ctrl++", "Ctrl++"),
+            ("space", "Space"),
+            ("esc", "Escape"),
+        ] {
+            assert_eq!(
+                Shortcut::parse_for(spec, false).unwrap().to_string(),
+                display
+            );
+        }
+    }
+}
@@ -5,16 +5,32 @@ See the [guide book](https://wishawa.github.io/async_ui/book/) to get started!
 
 */
 
+pub mod animation;
+pub mod clipboard;
 pub mod components;
+pub mod data_grid;
 pub mod executor;
+pub mod focus;
+pub mod gestures;
+pub mod keyboard;
 pub mod lists;
 mod mount;
 mod no_child;
+pub mod popover;
+pub mod router;
+mod selection;
 mod shortcuts;
+mod timer;
+pub mod toast;
+mod unique_id;
 
 pub use async_ui_internal_utils::reactive_cell::ReactiveCell;
 pub use async_ui_web_core::combinators::{join, race, race_ok, try_join};
+pub use async_ui_web_core::window;
+pub use async_ui_web_html::files;
+pub use async_ui_web_html::media;
 pub use async_ui_web_html::nodes as html;
+pub use async_ui_web_html::observers;
 pub use async_ui_web_macros::css;
 pub use async_ui_web_macros::select;
 pub use mount::{mount, mount_at};
@@ -24,6 +40,10 @@ pub use no_child::NoChild;
 pub mod __private_macro_only {
     #[doc(hidden)]
     pub use wasm_bindgen;
+    #[doc(hidden)]
+    pub mod router {
+        pub use crate::router::params::{parse_param, write_param};
+    }
 }
 
 pub mod event_handling {
@@ -31,22 +51,29 @@ pub mod event_handling {
     Types used in event handling mechanism.
     You shouldn't need to interact with this module directly often.
     */
-    pub use async_ui_web_html::events::EventFutureStream;
+    pub use async_ui_web_html::events::{EventFutureStream, OverflowPolicy};
 }
 
 pub mod event_traits {
     /*!
     Traits for event handling.
     */
-    pub use async_ui_web_html::events::{EmitElementEvent, EmitEvent, EmitHtmlElementEvent};
+    pub use super::gestures::EmitGestureEvent;
+    pub use async_ui_web_html::events::{
+        EmitDocumentEvent, EmitElementEvent, EmitEvent, EmitFormElementEvent, EmitHtmlElementEvent,
+        EmitMediaElementEvent, EmitWindowEvent,
+    };
 }
 
 pub mod shortcut_traits {
     /*!
     Traits provided for convenience.
      */
-    pub use super::shortcuts::{ShortcutClassList, ShortcutClassListBuilder, ShortcutRenderStr};
+    pub use super::shortcuts::{
+        ShortcutBind, ShortcutClassList, ShortcutClassListBuilder, ShortcutRenderStr,
+    };
     pub use async_ui_web_core::combinators::UiFutureExt;
+    pub use async_ui_web_html::media::MediaControl;
 }
 
 pub mod prelude_traits {
@@ -57,11 +84,15 @@ pub mod prelude_traits {
     use async_ui_web::prelude_traits::*;
     ```
      */
+    pub use super::gestures::EmitGestureEvent as _;
     pub use super::shortcuts::{
-        ShortcutClassList as _, ShortcutClassListBuilder as _, ShortcutRenderStr as _,
+        ShortcutBind as _, ShortcutClassList as _, ShortcutClassListBuilder as _,
+        ShortcutRenderStr as _,
     };
     pub use async_ui_web_core::combinators::UiFutureExt as _;
     pub use async_ui_web_html::events::{
-        EmitElementEvent as _, EmitEvent as _, EmitHtmlElementEvent as _,
+        EmitDocumentEvent as _, EmitElementEvent as _, EmitEvent as _, EmitFormElementEvent as _,
+        EmitHtmlElementEvent as _, EmitMediaElementEvent as _, EmitWindowEvent as _,
     };
+    pub use async_ui_web_html::media::MediaControl as _;
 }
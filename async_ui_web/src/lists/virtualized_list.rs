@@ -3,14 +3,12 @@ use std::{
     future::pending,
     hash::Hash,
     ops::{Range, RangeBounds},
-    rc::Rc,
 };
 
-use async_ui_internal_utils::reactive_cell::ReactiveCell;
 use async_ui_web_core::{combinators::join, ContainerNodeFuture};
+use async_ui_web_html::{events::EmitElementEvent, observers::until_intersection};
 use futures_lite::{Future, StreamExt};
-use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
-use web_sys::{Element, HtmlElement, IntersectionObserver, IntersectionObserverInit};
+use web_sys::{HtmlElement, IntersectionObserverInit};
 
 use super::DynamicList;
 
@@ -48,8 +46,6 @@ pub struct VirtualizedList<'c, Fut: Future + 'c, Renderer: FnMut(usize) -> Fut>
     spacers: (HtmlElement, HtmlElement),
     direction: Direction,
     root: &'c HtmlElement,
-    signal: Rc<ReactiveCell<()>>,
-    wake_closure: Closure<dyn Fn(JsValue)>,
 }
 
 struct State<Updater> {
@@ -104,19 +100,12 @@ impl<'c, Fut: Future + 'c, Renderer: FnMut(usize) -> Fut> VirtualizedList<'c, Fu
             range: 0..0,
             num_items: 0,
         };
-        let signal = Rc::new(ReactiveCell::new(()));
-        let signal_cloned = signal.clone();
         Self {
             list: DynamicList::new(),
             spacers: (spacer_front, spacer_back),
             state: RefCell::new(state),
             direction: Direction::Vertical,
             root,
-            signal,
-            wake_closure: Closure::new(move |_: JsValue| {
-                signal_cloned.borrow_mut();
-                async_ui_web_core::executor::run_now();
-            }),
         }
     }
     /// Should the list be for vertical or horizontal scrolling?
@@ -139,30 +128,16 @@ impl<'c, Fut: Future + 'c, Renderer: FnMut(usize) -> Fut> VirtualizedList<'c, Fu
 
         let [spf_render, spb_render] = [spf, spb]
             .map(|spacer| ContainerNodeFuture::new(pending::<()>(), spacer.clone().into()));
-        let _guard = scopeguard::guard((), |_| {
-            self.root
-                .remove_event_listener_with_callback(
-                    "scroll",
-                    self.wake_closure.as_ref().unchecked_ref(),
-                )
-                .unwrap_throw();
-        });
         join((spf_render, self.list.render(), spb_render, async {
-            let _observer = Observer::new(
-                self.root,
-                &[spf, spb],
-                self.wake_closure.as_ref().unchecked_ref(),
-            );
+            // look again whenever a spacer comes near the viewport, or the root scrolls
+            let mut options = IntersectionObserverInit::new();
+            options.root(Some(self.root)).root_margin("100%");
+            let mut changes = until_intersection(spf, &options)
+                .map(|_| ())
+                .or(until_intersection(spb, &options).map(|_| ()))
+                .or(self.root.until_scroll().map(|_| ()));
             self.update_visible();
-            self.root
-                .add_event_listener_with_callback(
-                    "scroll",
-                    self.wake_closure.as_ref().unchecked_ref(),
-                )
-                .unwrap_throw();
-            let mut uc = self.signal.until_change();
-            loop {
-                uc.next().await;
+            while changes.next().await.is_some() {
                 self.update_visible();
             }
         }))
@@ -228,28 +203,3 @@ impl<'c, Fut: Future + 'c, Renderer: FnMut(usize) -> Fut> VirtualizedList<'c, Fu
         state.range = new_start..new_end;
     }
 }
-
-struct Observer {
-    observer: IntersectionObserver,
-}
-
-impl Observer {
-    fn new(root: &Element, spacers: &[&HtmlElement], wake: &js_sys::Function) -> Self {
-        let observer = IntersectionObserver::new_with_options(
-            wake,
-            IntersectionObserverInit::new()
-                .root(Some(root))
-                .root_margin("100%"),
-        )
-        .unwrap_throw();
-        spacers.iter().for_each(|sp| observer.observe(sp));
-
-        Self { observer }
-    }
-}
-
-impl Drop for Observer {
-    fn drop(&mut self) {
-        self.observer.disconnect();
-    }
-}
@@ -0,0 +1,284 @@
+//! Floating content positioned next to an anchor element: [Popover], and [Tooltip] built on it.
+//!
+//! The floating element is kept next to its anchor as the page scrolls and resizes.
+//! When there isn't enough room on the preferred side, it flips to the other side,
+//! and it shifts along the anchor to stay in the viewport.
+//!
+//! Where the browser supports the [`popover` attribute](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/popover),
+//! the floating element is shown in the top layer, above everything else on the page
+//! (even modal dialogs) regardless of `z-index` and `overflow`.
+//! Elsewhere it is `position: fixed`, so you may need to give it a `z-index`.
+
+mod position;
+mod tooltip;
+
+use std::{cell::Cell, future::Future, ops::Deref};
+
+use async_ui_web_core::{
+    combinators::{race, UiFutureExt},
+    window::{DOCUMENT, WINDOW},
+};
+use async_ui_web_html::{events::EmitEvent, nodes::Div, observers::until_resize};
+use futures_lite::StreamExt;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Element, Event, EventTarget, HtmlElement, Node};
+
+use crate::{animation::animation_frames, shortcuts::ShortcutClassList, NoChild};
+
+use position::{compute_position, PhysicalSide, PositionOptions, Rect};
+
+pub use tooltip::Tooltip;
+
+/// Which side of the anchor a [Popover] goes on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Side {
+    Top,
+    #[default]
+    Bottom,
+    /// Left in left-to-right text, right in right-to-left text.
+    Start,
+    /// Right in left-to-right text, left in right-to-left text.
+    End,
+}
+
+/// How a [Popover] lines up with the anchor, along the anchor's side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Line up the left (or top) edges.
+    Start,
+    /// Center on the anchor.
+    #[default]
+    Center,
+    /// Line up the right (or bottom) edges.
+    End,
+}
+
+/// Where a [Popover] goes relative to its anchor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Placement {
+    pub side: Side,
+    pub align: Align,
+}
+
+impl Placement {
+    pub fn new(side: Side, align: Align) -> Self {
+        Self { side, align }
+    }
+}
+
+/// Minimum distance between a popover and the edges of the viewport.
+const VIEWPORT_PADDING: f64 = 4.0;
+
+/**
+Content that floats next to an anchor element.
+
+```
+# use async_ui_web::{popover::{Align, Placement, Popover, Side}, html::Button, join, prelude_traits::*};
+# let _ = async {
+let button = Button::new();
+let popover = Popover::new();
+popover.set_placement(Placement::new(Side::Top, Align::Start));
+join((
+    button.render("Anchor".render()),
+    popover.render(&button, "Floating content".render()),
+))
+.await;
+# };
+```
+
+The floating element is a `<div>` with the `popover` class and a `data-side`
+attribute (`top`, `bottom`, `left`, or `right`) telling which side of the anchor it ended up on,
+after flipping. It has an [arrow][Self::arrow] element, which is placed along
+that side to point at the anchor; style it with CSS to make it visible.
+
+This type [Deref]s to [Div], the floating element.
+ */
+pub struct Popover {
+    floating: Div,
+    arrow: Div,
+    placement: Cell<Placement>,
+    offset: Cell<f64>,
+    flip: Cell<bool>,
+    shift: Cell<bool>,
+}
+
+impl Default for Popover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Popover {
+    type Target = Div;
+    fn deref(&self) -> &Self::Target {
+        &self.floating
+    }
+}
+
+impl Popover {
+    /// Create a popover, placed below the anchor and centered on it.
+    pub fn new() -> Self {
+        let floating = Div::new();
+        floating.add_class("popover");
+        let arrow = Div::new();
+        arrow.add_class("popover-arrow");
+        arrow
+            .style()
+            .set_property("position", "absolute")
+            .unwrap_throw();
+        Self {
+            floating,
+            arrow,
+            placement: Cell::new(Placement::default()),
+            offset: Cell::new(8.0),
+            flip: Cell::new(true),
+            shift: Cell::new(true),
+        }
+    }
+    /// Set where the popover goes.
+    pub fn set_placement(&self, placement: Placement) {
+        self.placement.set(placement);
+    }
+    /// Set the gap between the anchor and the popover, in CSS pixels. Defaults to 8.
+    pub fn set_offset(&self, offset: f64) {
+        self.offset.set(offset);
+    }
+    /// Whether to move to the opposite side when the preferred side doesn't have room.
+    /// Defaults to `true`.
+    pub fn set_flip(&self, flip: bool) {
+        self.flip.set(flip);
+    }
+    /// Whether to slide along the anchor to stay in the viewport.
+    /// Defaults to `true`.
+    pub fn set_shift(&self, shift: bool) {
+        self.shift.set(shift);
+    }
+    /// The arrow element, a `<div>` with the `popover-arrow` class inside the popover.
+    ///
+    /// Its `left` (or `top`, for popovers on the left or right) is set so that its
+    /// center points at the anchor. Position it on the popover's edge with CSS,
+    /// using the popover's `data-side` attribute.
+    pub fn arrow(&self) -> &Div {
+        &self.arrow
+    }
+    /// Show the popover next to the given anchor, with the given content inside.
+    ///
+    /// The popover is shown until the returned future is dropped or completes.
+    /// It completes when the given future completes.
+    pub async fn render<F: Future>(&self, anchor: &Element, content: F) -> F::Output {
+        self.floating
+            .render(content.meanwhile(self.arrow.render(NoChild)))
+            .meanwhile(self.follow(anchor))
+            .await
+    }
+    async fn follow(&self, anchor: &Element) {
+        let floating: &HtmlElement = &self.floating;
+        let node: &Node = floating.as_ref();
+        while !node.is_connected() {
+            animation_frames().await;
+        }
+        self.show_in_top_layer();
+        let mut anchor_sizes = until_resize(anchor);
+        let mut floating_sizes = until_resize(floating);
+        let mut resizes =
+            WINDOW.with(|w| AsRef::<EventTarget>::as_ref(w).until_event::<Event>("resize".into()));
+        let mut scrolls = DOCUMENT
+            .with(|d| AsRef::<EventTarget>::as_ref(d).until_event::<Event>("scroll".into()));
+        // scroll events don't bubble, but this catches scrolling in any element
+        scrolls.set_capture(true);
+        scrolls.set_passive(true);
+        loop {
+            self.update(anchor);
+            race((
+                async {
+                    anchor_sizes.next().await;
+                },
+                async {
+                    floating_sizes.next().await;
+                },
+                async {
+                    resizes.next().await;
+                },
+                async {
+                    scrolls.next().await;
+                },
+            ))
+            .await;
+        }
+    }
+    fn show_in_top_layer(&self) {
+        let floating: &HtmlElement = &self.floating;
+        let style = floating.style();
+        // undo the centering of the user-agent styles for popovers
+        style.set_property("position", "fixed").unwrap_throw();
+        style.set_property("inset", "auto").unwrap_throw();
+        style.set_property("margin", "0").unwrap_throw();
+        // older browsers don't have `showPopover`, so look it up instead of calling it blindly
+        let show = js_sys::Reflect::get(floating, &"showPopover".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+        if let Some(show) = show {
+            floating.set_attribute("popover", "manual").unwrap_throw();
+            show.call0(floating).ok();
+        }
+    }
+    fn update(&self, anchor: &Element) {
+        let floating: &HtmlElement = &self.floating;
+        let anchor_rect = anchor.get_bounding_client_rect();
+        let floating_rect = floating.get_bounding_client_rect();
+        let (viewport_width, viewport_height) = DOCUMENT.with(|doc| {
+            let root = doc.document_element().unwrap_throw();
+            (root.client_width() as f64, root.client_height() as f64)
+        });
+        let placement = self.placement.get();
+        let rtl = WINDOW
+            .with(|w| w.get_computed_style(anchor).ok().flatten())
+            .and_then(|style| style.get_property_value("direction").ok())
+            .is_some_and(|direction| direction == "rtl");
+        let position = compute_position(
+            Rect {
+                x: anchor_rect.x(),
+                y: anchor_rect.y(),
+                width: anchor_rect.width(),
+                height: anchor_rect.height(),
+            },
+            (floating_rect.width(), floating_rect.height()),
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                width: viewport_width,
+                height: viewport_height,
+            },
+            &PositionOptions {
+                side: PhysicalSide::resolve(placement.side, rtl),
+                align: placement.align,
+                offset: self.offset.get(),
+                padding: VIEWPORT_PADDING,
+                flip: self.flip.get(),
+                shift: self.shift.get(),
+            },
+        );
+        let style = floating.style();
+        style
+            .set_property("left", &format!("{}px", position.x))
+            .unwrap_throw();
+        style
+            .set_property("top", &format!("{}px", position.y))
+            .unwrap_throw();
+        floating
+            .set_attribute("data-side", position.side.as_str())
+            .unwrap_throw();
+
+        let arrow_rect = self.arrow.get_bounding_client_rect();
+        let arrow_style = self.arrow.style();
+        let (along, across, half) = match position.side {
+            PhysicalSide::Top | PhysicalSide::Bottom => ("left", "top", arrow_rect.width() / 2.0),
+            PhysicalSide::Left | PhysicalSide::Right => ("top", "left", arrow_rect.height() / 2.0),
+        };
+        arrow_style
+            .set_property(along, &format!("{}px", position.arrow - half))
+            .unwrap_throw();
+        // the other coordinate is up to the stylesheet
+        arrow_style.remove_property(across).unwrap_throw();
+    }
+}
@@ -0,0 +1,274 @@
+//! Where to put a floating element next to its anchor. No DOM here, just geometry.
+
+use super::{Align, Side};
+
+/// A rectangle in viewport coordinates (CSS pixels).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    fn right(&self) -> f64 {
+        self.x + self.width
+    }
+    fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+}
+
+/// A [Side] with start and end resolved to left and right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PhysicalSide {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl PhysicalSide {
+    pub fn resolve(side: Side, rtl: bool) -> Self {
+        match (side, rtl) {
+            (Side::Top, _) => Self::Top,
+            (Side::Bottom, _) => Self::Bottom,
+            (Side::Start, false) | (Side::End, true) => Self::Left,
+            (Side::End, false) | (Side::Start, true) => Self::Right,
+        }
+    }
+    fn opposite(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+    fn is_vertical(self) -> bool {
+        matches!(self, Self::Top | Self::Bottom)
+    }
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+}
+
+pub(crate) struct PositionOptions {
+    pub side: PhysicalSide,
+    pub align: Align,
+    /// Gap between the anchor and the floating element.
+    pub offset: f64,
+    /// Minimum distance from the edges of the viewport.
+    pub padding: f64,
+    pub flip: bool,
+    pub shift: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Position {
+    pub x: f64,
+    pub y: f64,
+    /// The side actually used, after flipping.
+    pub side: PhysicalSide,
+    /// Where the arrow should point, measured from the floating element's
+    /// left edge (for top and bottom) or top edge (for left and right).
+    pub arrow: f64,
+}
+
+/// Keep the arrow at least this far from the floating element's corners.
+const ARROW_PADDING: f64 = 8.0;
+
+/// Position a floating element of the given size next to the anchor.
+pub(crate) fn compute_position(
+    anchor: Rect,
+    (width, height): (f64, f64),
+    viewport: Rect,
+    options: &PositionOptions,
+) -> Position {
+    // main axis: the coordinate that puts the floating element on `side` of the anchor
+    let main = |side| match side {
+        PhysicalSide::Top => anchor.y - options.offset - height,
+        PhysicalSide::Bottom => anchor.bottom() + options.offset,
+        PhysicalSide::Left => anchor.x - options.offset - width,
+        PhysicalSide::Right => anchor.right() + options.offset,
+    };
+    // how far the floating element would stick out of the viewport on `side`
+    let overflow = |side| match side {
+        PhysicalSide::Top => viewport.y + options.padding - main(side),
+        PhysicalSide::Bottom => main(side) + height - (viewport.bottom() - options.padding),
+        PhysicalSide::Left => viewport.x + options.padding - main(side),
+        PhysicalSide::Right => main(side) + width - (viewport.right() - options.padding),
+    };
+    let mut side = options.side;
+    if options.flip && overflow(side) > 0.0 && overflow(side.opposite()) < overflow(side) {
+        side = side.opposite();
+    }
+
+    let (anchor_start, anchor_size, size, viewport_start, viewport_end) = if side.is_vertical() {
+        (anchor.x, anchor.width, width, viewport.x, viewport.right())
+    } else {
+        (
+            anchor.y,
+            anchor.height,
+            height,
+            viewport.y,
+            viewport.bottom(),
+        )
+    };
+    let mut cross = match options.align {
+        Align::Start => anchor_start,
+        Align::Center => anchor_start + (anchor_size - size) / 2.0,
+        Align::End => anchor_start + anchor_size - size,
+    };
+    if options.shift {
+        let max = viewport_end - options.padding - size;
+        let min = viewport_start + options.padding;
+        // if it doesn't fit at all, keep the start visible
+        cross = cross.min(max).max(min);
+    }
+    let arrow = (anchor_start + anchor_size / 2.0 - cross)
+        .min(size - ARROW_PADDING)
+        .max(ARROW_PADDING.min(size / 2.0));
+
+    let (x, y) = if side.is_vertical() {
+        (cross, main(side))
+    } else {
+        (main(side), cross)
+    };
+    Position { x, y, side, arrow }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEWPORT: Rect = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 800.0,
+        height: 600.0,
+    };
+
+    fn options(side: PhysicalSide, align: Align) -> PositionOptions {
+        PositionOptions {
+            side,
+            align,
+            offset: 4.0,
+            padding: 2.0,
+            flip: true,
+            shift: true,
+        }
+    }
+
+    fn anchor(x: f64, y: f64) -> Rect {
+        Rect {
+            x,
+            y,
+            width: 100.0,
+            height: 20.0,
+        }
+    }
+
+    #[test]
+    fn placements() {
+        let a = anchor(300.0, 300.0);
+        let size = (50.0, 30.0);
+        let pos = compute_position(
+            a,
+            size,
+            VIEWPORT,
+            &options(PhysicalSide::Bottom, Align::Center),
+        );
+        assert_eq!(
+            (pos.x, pos.y, pos.side),
+            (325.0, 324.0, PhysicalSide::Bottom)
+        );
+        assert_eq!(pos.arrow, 25.0);
+        let pos = compute_position(a, size, VIEWPORT, &options(PhysicalSide::Top, Align::Start));
+        assert_eq!((pos.x, pos.y), (300.0, 266.0));
+        let pos = compute_position(a, size, VIEWPORT, &options(PhysicalSide::Right, Align::End));
+        assert_eq!((pos.x, pos.y), (404.0, 290.0));
+        let pos = compute_position(
+            a,
+            size,
+            VIEWPORT,
+            &options(PhysicalSide::Left, Align::Center),
+        );
+        assert_eq!((pos.x, pos.y, pos.side), (246.0, 295.0, PhysicalSide::Left));
+    }
+
+    #[test]
+    fn flips_when_out_of_room() {
+        let size = (50.0, 30.0);
+        // no room above
+        let pos = compute_position(
+            anchor(300.0, 10.0),
+            size,
+            VIEWPORT,
+            &options(PhysicalSide::Top, Align::Center),
+        );
+        assert_eq!((pos.y, pos.side), (34.0, PhysicalSide::Bottom));
+        // no room below
+        let pos = compute_position(
+            anchor(300.0, 570.0),
+            size,
+            VIEWPORT,
+            &options(PhysicalSide::Bottom, Align::Center),
+        );
+        assert_eq!((pos.y, pos.side), (536.0, PhysicalSide::Top));
+        // no room on either side: take the side with less overflow
+        let pos = compute_position(
+            anchor(300.0, 250.0),
+            (50.0, 400.0),
+            VIEWPORT,
+            &options(PhysicalSide::Top, Align::Center),
+        );
+        assert_eq!(pos.side, PhysicalSide::Bottom);
+        // flipping disabled
+        let mut opts = options(PhysicalSide::Top, Align::Center);
+        opts.flip = false;
+        let pos = compute_position(anchor(300.0, 10.0), size, VIEWPORT, &opts);
+        assert_eq!(pos.side, PhysicalSide::Top);
+    }
+
+    #[test]
+    fn shifts_into_view_and_keeps_arrow_on_anchor() {
+        // anchor at the right edge; centered, the floating element would stick out
+        let pos = compute_position(
+            anchor(750.0, 300.0),
+            (200.0, 30.0),
+            VIEWPORT,
+            &options(PhysicalSide::Bottom, Align::Center),
+        );
+        assert_eq!(pos.x, 598.0);
+        // the arrow still points at the anchor's center (x = 800)
+        assert_eq!(pos.x + pos.arrow, 790.0);
+        // arrow stays away from the corner
+        assert_eq!(pos.arrow, 192.0);
+
+        let mut opts = options(PhysicalSide::Bottom, Align::Center);
+        opts.shift = false;
+        let pos = compute_position(anchor(750.0, 300.0), (200.0, 30.0), VIEWPORT, &opts);
+        assert_eq!(pos.x, 700.0);
+        assert_eq!(pos.arrow, 100.0);
+    }
+
+    #[test]
+    fn start_and_end_follow_direction() {
+        assert_eq!(
+            PhysicalSide::resolve(Side::Start, false),
+            PhysicalSide::Left
+        );
+        assert_eq!(
+            PhysicalSide::resolve(Side::Start, true),
+            PhysicalSide::Right
+        );
+        assert_eq!(PhysicalSide::resolve(Side::End, true), PhysicalSide::Left);
+    }
+}
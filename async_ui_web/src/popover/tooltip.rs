@@ -0,0 +1,234 @@
+use std::{cell::Cell, ops::Deref, time::Duration};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::{
+    combinators::{join, race},
+    window::DOCUMENT,
+};
+use async_ui_web_html::{events::EmitEvent, nodes::Text};
+use futures_lite::StreamExt;
+use wasm_bindgen::UnwrapThrowExt;
+use web_sys::{Element, Event, EventTarget, KeyboardEvent};
+
+use super::{Align, Placement, Popover, Side};
+use crate::{timer::sleep, unique_id::unique_id};
+
+/**
+A short text shown next to an element while it is hovered or focused.
+
+```
+# use async_ui_web::{popover::Tooltip, html::Button, join, prelude_traits::*};
+# let _ = async {
+let button = Button::new();
+let tooltip = Tooltip::new("Save the document");
+join((button.render("💾".render()), tooltip.render(&button))).await;
+# };
+```
+
+The tooltip appears after the pointer rests on the anchor (or the anchor is focused)
+for a moment, and goes away shortly after the pointer leaves (or focus moves away),
+or right away when Escape is pressed (it then stays hidden until the anchor is entered
+or focused again). While shown, it is added to the anchor's `aria-describedby`.
+
+This type [Deref]s to the [Popover] it is shown in (placed above the anchor by default),
+which has the `role="tooltip"` attribute.
+ */
+pub struct Tooltip {
+    popover: Popover,
+    text: Text,
+    show_delay: Cell<Duration>,
+    hide_delay: Cell<Duration>,
+    /// Whether the anchor is hovered or focused.
+    wanted: ReactiveCell<bool>,
+    /// Set when Escape is pressed, to hide without the delay.
+    escaped: Cell<bool>,
+}
+
+impl Deref for Tooltip {
+    type Target = Popover;
+    fn deref(&self) -> &Self::Target {
+        &self.popover
+    }
+}
+
+impl Tooltip {
+    /// Create a tooltip with the given text.
+    pub fn new(text: &str) -> Self {
+        let popover = Popover::new();
+        popover.set_placement(Placement::new(Side::Top, Align::Center));
+        popover.set_attribute("role", "tooltip").unwrap_throw();
+        popover.set_id(&unique_id("tooltip"));
+        let node = Text::new();
+        node.set_data(text);
+        Self {
+            popover,
+            text: node,
+            show_delay: Cell::new(Duration::from_millis(500)),
+            hide_delay: Cell::new(Duration::from_millis(100)),
+            wanted: ReactiveCell::new(false),
+            escaped: Cell::new(false),
+        }
+    }
+    /// Change the text.
+    pub fn set_text(&self, text: &str) {
+        self.text.set_data(text);
+    }
+    /// Set how long the anchor must be hovered or focused before the tooltip shows,
+    /// and how long it stays after that ends. Defaults to 500 and 100 milliseconds.
+    pub fn set_delays(&self, show: Duration, hide: Duration) {
+        self.show_delay.set(show);
+        self.hide_delay.set(hide);
+    }
+    /// Show the tooltip for the given anchor whenever it is hovered or focused.
+    ///
+    /// This async method never completes.
+    pub async fn render(&self, anchor: &Element) {
+        join((self.track(anchor), self.show_when_wanted(anchor))).await;
+    }
+    async fn track(&self, anchor: &Element) {
+        let target: &EventTarget = anchor.as_ref();
+        let mut hovered = false;
+        let mut focused = false;
+        let mut events = target
+            .until_event::<Event>("pointerenter".into())
+            .map(|_| (Some(true), None))
+            .or(target
+                .until_event::<Event>("pointerleave".into())
+                .map(|_| (Some(false), None)))
+            .or(target
+                .until_event::<Event>("focusin".into())
+                .map(|_| (None, Some(true))))
+            .or(target
+                .until_event::<Event>("focusout".into())
+                .map(|_| (None, Some(false))));
+        let mut keydowns = DOCUMENT.with(|doc| {
+            AsRef::<EventTarget>::as_ref(doc).until_event::<KeyboardEvent>("keydown".into())
+        });
+        // after Escape, stay hidden until the pointer enters or focus comes back
+        let mut dismissed = false;
+        loop {
+            let event = race((async { Some(events.next().await.unwrap_throw()) }, async {
+                while keydowns.next().await.unwrap_throw().key() != "Escape" {}
+                None
+            }))
+            .await;
+            match event {
+                Some((hover, focus)) => {
+                    if hover == Some(true) || focus == Some(true) {
+                        dismissed = false;
+                    }
+                    hovered = hover.unwrap_or(hovered);
+                    focused = focus.unwrap_or(focused);
+                }
+                None => {
+                    dismissed = true;
+                    self.escaped.set(true);
+                }
+            }
+            let wanted = !dismissed && (hovered || focused);
+            if *self.wanted.borrow() != wanted {
+                *self.wanted.borrow_mut() = wanted;
+            }
+        }
+    }
+    /// Wait for `delay`, as long as `wanted` stays `value`.
+    /// Returns whether it did.
+    async fn hold(&self, value: bool, delay: Duration) -> bool {
+        race((
+            async {
+                sleep(delay).await;
+                true
+            },
+            async {
+                while *self.wanted.borrow() == value {
+                    self.wanted.until_change().await;
+                }
+                false
+            },
+        ))
+        .await
+    }
+    async fn show_when_wanted(&self, anchor: &Element) {
+        loop {
+            while !*self.wanted.borrow() {
+                self.wanted.until_change().await;
+            }
+            if !self.hold(true, self.show_delay.get()).await {
+                continue;
+            }
+            self.escaped.set(false);
+            let id = self.popover.id();
+            let _describe = describe(anchor, &id);
+            race((self.popover.render(anchor, self.text.render()), async {
+                loop {
+                    while *self.wanted.borrow() {
+                        self.wanted.until_change().await;
+                    }
+                    if self.escaped.get() || self.hold(false, self.hide_delay.get()).await {
+                        break;
+                    }
+                }
+            }))
+            .await;
+        }
+    }
+}
+
+/// Add `id` to the `aria-describedby` of the element, keeping what is already there.
+/// The returned guard takes it out again, unless it was there to begin with.
+fn describe<'a>(element: &'a Element, id: &'a str) -> impl Drop + 'a {
+    let described_by = element.get_attribute("aria-describedby");
+    let added = described_by
+        .as_deref()
+        .is_none_or(|list| !list.split_ascii_whitespace().any(|t| t == id));
+    if added {
+        let list = described_by.unwrap_or_default();
+        element
+            .set_attribute("aria-describedby", &add_token(&list, id))
+            .unwrap_throw();
+    }
+    scopeguard::guard((), move |_| {
+        if !added {
+            return;
+        }
+        let list = element
+            .get_attribute("aria-describedby")
+            .unwrap_or_default();
+        match remove_token(&list, id) {
+            rest if rest.is_empty() => element.remove_attribute("aria-describedby").ok(),
+            rest => element.set_attribute("aria-describedby", &rest).ok(),
+        };
+    })
+}
+
+/// Add a token to the end of a space-separated list.
+fn add_token(list: &str, token: &str) -> String {
+    if list.trim().is_empty() {
+        token.to_owned()
+    } else {
+        format!("{} {token}", list.trim_end())
+    }
+}
+
+/// Remove a token from a space-separated list.
+fn remove_token(list: &str, token: &str) -> String {
+    list.split_ascii_whitespace()
+        .filter(|t| *t != token)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describedby_tokens() {
+        assert_eq!(add_token("", "tip"), "tip");
+        assert_eq!(add_token("hint", "tip"), "hint tip");
+        assert_eq!(add_token("  ", "tip"), "tip");
+        assert_eq!(remove_token("hint tip", "tip"), "hint");
+        assert_eq!(remove_token("tip", "tip"), "");
+        assert_eq!(remove_token("hint", "tip"), "hint");
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    ops::Deref,
+};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::{
+    combinators::{join, race, UiFutureExt},
+    window::WINDOW,
+};
+use async_ui_web_html::{events::EmitElementEvent, nodes::Anchor};
+use futures_lite::StreamExt;
+use wasm_bindgen::UnwrapThrowExt;
+
+use crate::shortcuts::ShortcutClassList;
+
+use super::{
+    navigate,
+    params::{decode_segment, split_path},
+    replace, scroll_to_target, set_scroll_target, until_location_change, Routes,
+};
+
+/// Where a [Link] scrolls the page to after navigating.
+///
+/// Links with a `#hash` always scroll the element with that ID into view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LinkScroll {
+    /// Scroll to the top of the page.
+    #[default]
+    Top,
+    /// Leave the scroll position alone.
+    Keep,
+}
+
+/**
+An `<a>` that navigates with the [router][super] instead of reloading the page.
+
+```
+# use async_ui_web::{router::{Link, Routes}, prelude_traits::*};
+# #[derive(Routes)]
+# enum Page {
+#     #[route("/users/:id")]
+#     User { id: u32 },
+# }
+# let _ = async {
+let link = Link::to(&Page::User { id: 42 });
+link.render("Profile".render()).await;
+# };
+```
+
+Plain left-clicks are handled by [navigate] (or [replace][Self::set_replace]).
+Clicks with a modifier key (to open in a new tab or window), clicks on links with
+a `target` or `download` attribute, and links to other origins are left to the browser.
+
+While its target is the current page, the link has the "active" class
+(see [set_active_class][Self::set_active_class] and [set_exact][Self::set_exact]).
+
+This type [Deref]s to [Anchor], so all the HTML methods are available on it.
+ */
+pub struct Link {
+    anchor: Anchor,
+    replace: Cell<bool>,
+    scroll: Cell<LinkScroll>,
+    exact: Cell<bool>,
+    active_class: RefCell<String>,
+    /// Notified when something affecting the active state is changed.
+    settings: ReactiveCell<()>,
+}
+
+impl Deref for Link {
+    type Target = Anchor;
+    fn deref(&self) -> &Self::Target {
+        &self.anchor
+    }
+}
+
+impl Link {
+    /// Create a link to the given URL (usually a path such as `"/users/42"`).
+    pub fn new(href: &str) -> Self {
+        let anchor = Anchor::new();
+        anchor.set_href(href);
+        Self {
+            anchor,
+            replace: Cell::new(false),
+            scroll: Cell::new(LinkScroll::default()),
+            exact: Cell::new(true),
+            active_class: RefCell::new(String::from("active")),
+            settings: ReactiveCell::new(()),
+        }
+    }
+    /// Create a link to the given route.
+    ///
+    /// For a route of a [nested][super::Router::nested] router, this is only the
+    /// nested part of the path; use [new][Self::new] with the full path instead.
+    pub fn to<R: Routes>(route: &R) -> Self {
+        Self::new(&route.to_path())
+    }
+    /// Change the URL the link goes to.
+    pub fn set_href(&self, href: &str) {
+        self.anchor.set_href(href);
+        self.settings_changed();
+    }
+    /// Replace the current history entry when clicked, instead of adding one.
+    /// Defaults to `false`.
+    pub fn set_replace(&self, replace: bool) {
+        self.replace.set(replace);
+    }
+    /// Where to scroll after navigating. Defaults to [LinkScroll::Top].
+    pub fn set_scroll(&self, scroll: LinkScroll) {
+        self.scroll.set(scroll);
+    }
+    /// If `true` (the default), the link is active only when the current path is its target.
+    /// If `false`, it is also active on paths under its target, so that a link to
+    /// `"/settings"` is active on `"/settings/profile"`.
+    pub fn set_exact(&self, exact: bool) {
+        self.exact.set(exact);
+        self.settings_changed();
+    }
+    /// The class added while the link is active. Defaults to `"active"`.
+    pub fn set_active_class(&self, class: &str) {
+        self.anchor.del_class(&self.active_class.borrow());
+        *self.active_class.borrow_mut() = class.to_owned();
+        self.settings_changed();
+    }
+    fn settings_changed(&self) {
+        *self.settings.borrow_mut() = ();
+    }
+    /// Put the link on the screen, and handle clicks on it.
+    ///
+    /// The returned future completes when the given future (the link's content) completes.
+    ///
+    /// This method should only be called once. It may misbehave otherwise.
+    pub async fn render<F: Future>(&self, c: F) -> F::Output {
+        self.anchor
+            .render(c)
+            .meanwhile(join((
+                self.handle_clicks(),
+                self.track_active(),
+                scroll_to_target(),
+            )))
+            .await
+    }
+    async fn handle_clicks(&self) {
+        let mut clicks = self.anchor.until_click();
+        while let Some(ev) = clicks.next().await {
+            // This runs while the event is being dispatched, so preventing
+            // the default still stops the browser from following the link.
+            if ev.default_prevented()
+                || ev.button() != 0
+                || ev.ctrl_key()
+                || ev.meta_key()
+                || ev.shift_key()
+                || ev.alt_key()
+                || !matches!(&*self.anchor.target(), "" | "_self")
+                || self.anchor.has_attribute("download")
+                || self.anchor.origin() != WINDOW.with(|w| w.location().origin().unwrap_throw())
+            {
+                continue;
+            }
+            ev.prevent_default();
+            let hash = self.anchor.hash();
+            let url = format!("{}{}{}", self.anchor.pathname(), self.anchor.search(), hash);
+            if self.replace.get() {
+                replace(&url);
+            } else {
+                navigate(&url);
+            }
+            if let Some(id) = hash.strip_prefix('#').filter(|id| !id.is_empty()) {
+                // Once the new page has rendered; this link may be gone by then.
+                set_scroll_target(decode_segment(id));
+                continue;
+            }
+            set_scroll_target(None);
+            if self.scroll.get() == LinkScroll::Top {
+                WINDOW.with(|w| w.scroll_to_with_x_and_y(0.0, 0.0));
+            }
+        }
+    }
+    async fn track_active(&self) {
+        loop {
+            let active = is_active(
+                &super::current_path(),
+                &self.anchor.pathname(),
+                self.exact.get(),
+            );
+            self.anchor.set_class(&self.active_class.borrow(), active);
+            race((until_location_change(), self.settings.until_change())).await;
+        }
+    }
+}
+
+/// Whether a link to `target` is active on the page at `current`.
+fn is_active(current: &str, target: &str, exact: bool) -> bool {
+    let current = split_path(current);
+    let target = split_path(target);
+    if exact {
+        current == target
+    } else {
+        current.starts_with(&target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_active;
+
+    #[test]
+    fn active_paths() {
+        assert!(is_active("/users/42", "/users/42", true));
+        assert!(is_active("/users/42/", "/users/42", true));
+        assert!(!is_active("/users/42/posts", "/users/42", true));
+        assert!(is_active("/users/42/posts", "/users/42", false));
+        assert!(!is_active("/users/420", "/users/42", false));
+        assert!(is_active("/", "/", true));
+        assert!(is_active("/anything", "/", false));
+    }
+}
@@ -0,0 +1,293 @@
+//! Client-side routing with the History API.
+//!
+//! Describe the pages of your app as an enum, and derive [Routes] for it.
+//! Each variant gets a path template; `:name` segments are parameters,
+//! parsed into the variant's fields with [FromStr][std::str::FromStr] and written with
+//! [Display][std::fmt::Display].
+//! A template ending in `/*` matches any path that starts with it,
+//! leaving the rest for a [nested][Router::nested] router.
+//!
+//! ```
+//! # use async_ui_web::{router::{Router, Routes, navigate}, html::Button, join, prelude_traits::*};
+//! #[derive(Routes, Clone, PartialEq)]
+//! enum Page {
+//!     #[route("/")]
+//!     Home,
+//!     #[route("/users/:id")]
+//!     User { id: u32 },
+//!     #[route("/settings/*")]
+//!     Settings,
+//! }
+//!
+//! #[derive(Routes, Clone, PartialEq)]
+//! enum SettingsPage {
+//!     #[route("/")]
+//!     General,
+//!     #[route("/profile")]
+//!     Profile,
+//! }
+//!
+//! # let _ = async {
+//! let router = &Router::<Page>::new();
+//! router
+//!     .render(|page| async move {
+//!         match page {
+//!             Some(Page::Home) => {
+//!                 let button = Button::new();
+//!                 join((button.render("Me".render()), async {
+//!                     button.until_click().await;
+//!                     navigate(&Page::User { id: 1 }.to_path());
+//!                 }))
+//!                 .await;
+//!             }
+//!             Some(Page::User { id }) => format!("user {id}").render().await,
+//!             Some(Page::Settings) => {
+//!                 // matches "/settings" and "/settings/profile"
+//!                 let nested = router.nested::<SettingsPage>();
+//!                 nested
+//!                     .render(|page| async move {
+//!                         match page {
+//!                             Some(SettingsPage::General) => "general".render().await,
+//!                             Some(SettingsPage::Profile) => "profile".render().await,
+//!                             None => "no such setting".render().await,
+//!                         }
+//!                     })
+//!                     .await
+//!             }
+//!             None => "not found".render().await,
+//!         }
+//!     })
+//!     .await;
+//! # };
+//! ```
+//!
+//! Routers follow the URL: they update after [navigate] and [replace],
+//! and when the user goes back or forward (the `popstate` event).
+//! Use [Link] for links that navigate without reloading the page.
+//! Only the path is matched; the query string and the hash are ignored.
+
+mod link;
+pub(crate) mod params;
+
+use std::{cell::Cell, future::Future, rc::Rc};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::{
+    combinators::{join, UiFutureExt},
+    window::{DOCUMENT, WINDOW},
+};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+
+use crate::{animation::animation_frames, components::DynamicSlot};
+
+use params::split_path;
+
+pub use link::{Link, LinkScroll};
+
+/// An enum of the pages of an app, matched against URL paths by a [Router].
+///
+/// Derive this with `#[derive(Routes)]`, giving each variant a `#[route("/path")]`
+/// attribute; see the [module documentation][self].
+pub use async_ui_web_macros::Routes;
+
+/// A set of routes that can be matched against URL paths.
+///
+/// This is usually derived; see the [module documentation][self].
+pub trait Routes: Sized {
+    /// Match the start of the given path segments.
+    /// Returns the route and how many segments it used.
+    ///
+    /// Routes are tried in declaration order, and the first match wins.
+    fn match_segments(segments: &[&str]) -> Option<(Self, usize)>;
+    /// The path for this route, starting with `/`.
+    ///
+    /// For a route nested under another, this is only the nested part.
+    fn to_path(&self) -> String;
+    /// Match a whole path such as `"/users/42"`.
+    fn from_path(path: &str) -> Option<Self> {
+        Self::match_segments(&split_path(path)).map(|(route, _)| route)
+    }
+}
+
+thread_local! {
+    /// Bumped every time the URL changes.
+    static LOCATION_VERSION: Rc<ReactiveCell<u64>> = {
+        // Listen for back/forward navigation.
+        // This listener is never removed, since it serves every router on the page.
+        let closure = Closure::<dyn Fn()>::new(|| {
+            location_changed();
+            async_ui_web_core::executor::run_now();
+        });
+        WINDOW.with(|window| {
+            window
+                .add_event_listener_with_callback("popstate", closure.as_ref().unchecked_ref())
+                .unwrap_throw()
+        });
+        closure.forget();
+        Rc::new(ReactiveCell::new(0))
+    };
+}
+
+thread_local! {
+    /// The ID of the element to scroll to once the page for the new URL has rendered.
+    static SCROLL_TARGET: Rc<ReactiveCell<Option<String>>> = Rc::new(ReactiveCell::new(None));
+}
+
+/// Scroll to the element with the given ID after the next frame (see [scroll_to_target]),
+/// or with `None`, cancel a scroll that hasn't happened yet.
+pub(crate) fn set_scroll_target(id: Option<String>) {
+    SCROLL_TARGET.with(|target| {
+        if id.is_some() || target.borrow().is_some() {
+            *target.borrow_mut() = id;
+        }
+    });
+}
+
+/// Scroll to the element given to [set_scroll_target], a frame after it is given
+/// so that the new page has been rendered.
+///
+/// Every rendered [Router] and [Link] runs this, so that it still happens when the
+/// clicked link was on the page that has just been replaced.
+pub(crate) async fn scroll_to_target() {
+    let target = SCROLL_TARGET.with(Rc::clone);
+    loop {
+        while target.borrow().is_none() {
+            target.until_change().await;
+        }
+        animation_frames().await;
+        // someone else may have scrolled to it already
+        let Some(id) = target.borrow_mut().take() else {
+            continue;
+        };
+        if let Some(element) = DOCUMENT.with(|doc| doc.get_element_by_id(&id)) {
+            element.scroll_into_view();
+        }
+    }
+}
+
+fn location_changed() {
+    LOCATION_VERSION.with(|version| *version.borrow_mut() += 1);
+}
+
+/// Wait until the URL changes.
+pub(crate) async fn until_location_change() {
+    let version = LOCATION_VERSION.with(Rc::clone);
+    version.until_change().await;
+}
+
+/// The path part of the current URL, such as `"/users/42"`.
+pub fn current_path() -> String {
+    WINDOW.with(|window| window.location().pathname().unwrap_throw())
+}
+
+/// Go to the given URL (usually a path such as `"/users/42"`) without
+/// reloading the page, adding an entry to the browser's history.
+pub fn navigate(url: &str) {
+    WINDOW.with(|window| {
+        window
+            .history()
+            .unwrap_throw()
+            .push_state_with_url(&JsValue::NULL, "", Some(url))
+            .unwrap_throw()
+    });
+    location_changed();
+}
+
+/// Like [navigate], but replace the current history entry instead of adding one.
+pub fn replace(url: &str) {
+    WINDOW.with(|window| {
+        window
+            .history()
+            .unwrap_throw()
+            .replace_state_with_url(&JsValue::NULL, "", Some(url))
+            .unwrap_throw()
+    });
+    location_changed();
+}
+
+/**
+Renders the page matching the current URL.
+
+See the [module documentation][self] for an example.
+ */
+pub struct Router<R> {
+    /// How many segments of the path belong to parent routers.
+    base: usize,
+    /// How many segments the current route used.
+    consumed: Cell<usize>,
+    route: ReactiveCell<Option<R>>,
+}
+
+impl<R: Routes + Clone + PartialEq> Default for Router<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Routes + Clone + PartialEq> Router<R> {
+    /// Create a router matching the whole path.
+    pub fn new() -> Self {
+        Self::with_base(0)
+    }
+    fn with_base(base: usize) -> Self {
+        let router = Self {
+            base,
+            consumed: Cell::new(0),
+            route: ReactiveCell::new(None),
+        };
+        router.update();
+        router
+    }
+    /// Create a router for the part of the path after this router's current route.
+    /// Use it from inside a route whose template ends in `/*`.
+    pub fn nested<S: Routes + Clone + PartialEq>(&self) -> Router<S> {
+        Router::with_base(self.base + self.consumed.get())
+    }
+    /// The current route, or `None` if the path doesn't match any route.
+    ///
+    /// This is updated while the router is [rendered][Self::render].
+    pub fn route(&self) -> &ReactiveCell<Option<R>> {
+        &self.route
+    }
+    fn update(&self) {
+        let path = current_path();
+        let segments = split_path(&path);
+        let matched = segments
+            .get(self.base..)
+            .and_then(|segments| R::match_segments(segments));
+        let (route, consumed) = match matched {
+            Some((route, consumed)) => (Some(route), consumed),
+            None => (None, 0),
+        };
+        self.consumed.set(consumed);
+        if *self.route.borrow() != route {
+            *self.route.borrow_mut() = route;
+        }
+    }
+    /// Render the page for the current route, and switch pages when the route changes.
+    ///
+    /// The given function is called with the matched route (or `None` if nothing matches)
+    /// each time the route changes, and the future it returns is rendered in place of the
+    /// previous one. When only the part of the path after a `/*` route changes,
+    /// the page is kept, and nested routers update on their own.
+    ///
+    /// This async method never completes.
+    pub async fn render<F: Future<Output = ()>>(&self, page: impl Fn(Option<R>) -> F) {
+        let slot = DynamicSlot::new();
+        let follow_location = async {
+            let mut rendered = None;
+            loop {
+                self.update();
+                let route = self.route.borrow().clone();
+                if rendered.as_ref() != Some(&route) {
+                    slot.set_future(page(route.clone()));
+                    rendered = Some(route);
+                }
+                until_location_change().await;
+            }
+        };
+        slot.render()
+            .meanwhile(join((follow_location, scroll_to_target())))
+            .await
+    }
+}
@@ -0,0 +1,82 @@
+//! Converting route parameters to and from path segments.
+
+use std::{fmt::Display, str::FromStr};
+
+/// Percent-decode a path segment and parse it.
+pub fn parse_param<T: FromStr>(segment: &str) -> Option<T> {
+    decode_segment(segment)?.parse().ok()
+}
+
+/// Format a parameter and append it to the path, percent-encoded.
+pub fn write_param<T: Display + ?Sized>(path: &mut String, value: &T) {
+    encode_segment_into(path, &value.to_string());
+}
+
+/// Characters allowed in a path segment without encoding (RFC 3986 `pchar`).
+fn is_segment_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&b)
+}
+
+pub(crate) fn encode_segment_into(out: &mut String, segment: &str) {
+    for &b in segment.as_bytes() {
+        if is_segment_char(b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+}
+
+/// Decode `%XX` escapes. `None` if an escape is malformed or the result isn't UTF-8.
+pub(crate) fn decode_segment(segment: &str) -> Option<String> {
+    if !segment.contains('%') {
+        return Some(segment.to_owned());
+    }
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Split a path into its non-empty segments.
+pub(crate) fn split_path(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_round_trip() {
+        for value in ["plain", "with space", "a/b", "100%", "ünïcødé", "a?b#c"] {
+            let mut encoded = String::new();
+            encode_segment_into(&mut encoded, value);
+            assert!(!encoded.contains(['/', ' ', '?', '#']), "{encoded}");
+            assert_eq!(decode_segment(&encoded).as_deref(), Some(value));
+        }
+        assert_eq!(decode_segment("%"), None);
+        assert_eq!(decode_segment("%zz"), None);
+        assert_eq!(decode_segment("%FF"), None);
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(parse_param::<u32>("42"), Some(42));
+        assert_eq!(parse_param::<u32>("x"), None);
+        assert_eq!(parse_param::<String>("a%20b").as_deref(), Some("a b"));
+        let mut path = String::from("/");
+        write_param(&mut path, "a b");
+        assert_eq!(path, "/a%20b");
+    }
+}
@@ -0,0 +1,109 @@
+//! Selecting items of a list or tree by clicking, shared by
+//! [DataGrid][crate::data_grid::DataGrid] and [TreeView][crate::components::TreeView].
+
+use std::{collections::HashSet, hash::Hash};
+
+/// Whether clicking items (such as the rows of a [DataGrid][crate::data_grid::DataGrid])
+/// selects them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum SelectionMode {
+    /// Items can't be selected.
+    #[default]
+    None,
+    /// Clicking an item selects it, and only it.
+    Single,
+    /// Ctrl-click (Cmd-click on macOS) adds or removes an item, and Shift-click selects a range.
+    Multiple,
+}
+
+/// Modifier keys held during a click.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ClickModifiers {
+    /// Ctrl, or Cmd on macOS.
+    pub toggle: bool,
+    pub shift: bool,
+}
+
+/// Update the selection for a click on `item`.
+///
+/// `anchor` is the item last clicked without Shift; Shift-clicks select from there
+/// to the clicked item, in the order of `view`.
+pub(crate) fn click_select<K: Eq + Hash + Clone>(
+    selection: &mut HashSet<K>,
+    anchor: &mut Option<K>,
+    view: &[K],
+    item: &K,
+    mode: SelectionMode,
+    modifiers: ClickModifiers,
+) {
+    match mode {
+        SelectionMode::None => {}
+        SelectionMode::Single => {
+            selection.clear();
+            selection.insert(item.clone());
+            *anchor = Some(item.clone());
+        }
+        SelectionMode::Multiple => {
+            let position = |item: &K| view.iter().position(|i| i == item);
+            let range = anchor
+                .as_ref()
+                .and_then(position)
+                .zip(position(item))
+                .filter(|_| modifiers.shift);
+            if let Some((from, to)) = range {
+                if !modifiers.toggle {
+                    selection.clear();
+                }
+                selection.extend(view[from.min(to)..=from.max(to)].iter().cloned());
+            } else if modifiers.toggle {
+                if !selection.remove(item) {
+                    selection.insert(item.clone());
+                }
+                *anchor = Some(item.clone());
+            } else {
+                selection.clear();
+                selection.insert(item.clone());
+                *anchor = Some(item.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_on_click() {
+        let view = [4, 2, 0, 1, 3];
+        let mut selection = HashSet::new();
+        let mut anchor = None;
+        let mut click = |item, mode, toggle, shift| {
+            click_select(
+                &mut selection,
+                &mut anchor,
+                &view,
+                &item,
+                mode,
+                ClickModifiers { toggle, shift },
+            );
+            let mut selected: Vec<_> = selection.iter().copied().collect();
+            selected.sort();
+            selected
+        };
+        assert_eq!(click(2, SelectionMode::None, false, false), []);
+        assert_eq!(click(2, SelectionMode::Single, false, false), [2]);
+        assert_eq!(click(1, SelectionMode::Single, true, true), [1]);
+        assert_eq!(click(2, SelectionMode::Multiple, false, false), [2]);
+        // range in view order: 2, 0, 1
+        assert_eq!(click(1, SelectionMode::Multiple, false, true), [0, 1, 2]);
+        assert_eq!(click(0, SelectionMode::Multiple, true, false), [1, 2]);
+        assert_eq!(click(3, SelectionMode::Multiple, true, false), [1, 2, 3]);
+        // Ctrl+Shift adds the range to the selection: from 3 back to 4 is the whole view
+        assert_eq!(
+            click(4, SelectionMode::Multiple, true, true),
+            [0, 1, 2, 3, 4]
+        );
+        assert_eq!(click(4, SelectionMode::Multiple, false, false), [4]);
+    }
+}
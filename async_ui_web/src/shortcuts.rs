@@ -1,7 +1,8 @@
-use std::future::Pending;
+use std::future::{Future, Pending};
 
 use async_ui_web_core::ContainerNodeFuture;
 use async_ui_web_html::nodes::Text;
+use futures_lite::{Stream, StreamExt};
 use js_sys::Array;
 use wasm_bindgen::{JsValue, UnwrapThrowExt};
 
@@ -106,3 +107,107 @@ pub trait ShortcutClassListBuilder: AsRef<web_sys::Element> {
     }
 }
 impl<T: AsRef<web_sys::Element>> ShortcutClassListBuilder for T {}
+
+pub trait ShortcutBind: AsRef<web_sys::Element> {
+    /// Add or remove a classname according to the `bool`s from the Stream.
+    ///
+    /// The returned Future keeps the class in sync until the Stream ends.
+    /// Join it with the element's render.
+    /// ```
+    /// # let _ = async {
+    /// # use async_ui_web::{html::Div, join, prelude_traits::*, ReactiveCell};
+    /// # let children = std::future::pending::<()>();
+    /// let selected = ReactiveCell::new(false);
+    /// let div = Div::new();
+    /// join((
+    ///     div.render(children),
+    ///     div.bind_class("selected", selected.watch(|s| *s)),
+    /// )).await;
+    /// # };
+    /// ```
+    fn bind_class<'a>(
+        &'a self,
+        c: &'a str,
+        included: impl Stream<Item = bool> + 'a,
+    ) -> impl Future<Output = ()> + 'a {
+        async move {
+            let mut included = std::pin::pin!(included);
+            while let Some(included) = included.next().await {
+                self.as_ref().set_class(c, included);
+            }
+        }
+    }
+    /// Set or remove an attribute according to the values from the Stream.
+    ///
+    /// `Some(value)` sets the attribute to the value. `None` removes the attribute.
+    ///
+    /// The returned Future keeps the attribute in sync until the Stream ends.
+    /// Join it with the element's render.
+    /// ```
+    /// # let _ = async {
+    /// # use async_ui_web::{html::Input, join, prelude_traits::*, ReactiveCell};
+    /// let hint = ReactiveCell::new(String::from("Search"));
+    /// let input = Input::new();
+    /// join((
+    ///     input.render(),
+    ///     input.bind_attr("placeholder", hint.watch(|h| Some(h.clone()))),
+    /// )).await;
+    /// # };
+    /// ```
+    fn bind_attr<'a, S: AsRef<str>>(
+        &'a self,
+        name: &'a str,
+        values: impl Stream<Item = Option<S>> + 'a,
+    ) -> impl Future<Output = ()> + 'a {
+        async move {
+            let element = self.as_ref();
+            let mut values = std::pin::pin!(values);
+            while let Some(value) = values.next().await {
+                match value {
+                    Some(value) => element.set_attribute(name, value.as_ref()),
+                    None => element.remove_attribute(name),
+                }
+                .unwrap_throw();
+            }
+        }
+    }
+    /// Set or remove an inline style property according to the values from the Stream.
+    ///
+    /// `Some(value)` sets the property to the value. `None` removes the property.
+    ///
+    /// The returned Future keeps the style in sync until the Stream ends.
+    /// Join it with the element's render.
+    /// ```
+    /// # let _ = async {
+    /// # use async_ui_web::{html::Div, join, prelude_traits::*, ReactiveCell};
+    /// # let children = std::future::pending::<()>();
+    /// let progress = ReactiveCell::new(0.0);
+    /// let bar = Div::new();
+    /// join((
+    ///     bar.render(children),
+    ///     bar.bind_style("width", progress.watch(|p| Some(format!("{}%", p * 100.0)))),
+    /// )).await;
+    /// # };
+    /// ```
+    fn bind_style<'a, S: AsRef<str>>(
+        &'a self,
+        property: &'a str,
+        values: impl Stream<Item = Option<S>> + 'a,
+    ) -> impl Future<Output = ()> + 'a
+    where
+        Self: AsRef<web_sys::HtmlElement>,
+    {
+        async move {
+            let style = AsRef::<web_sys::HtmlElement>::as_ref(self).style();
+            let mut values = std::pin::pin!(values);
+            while let Some(value) = values.next().await {
+                match value {
+                    Some(value) => style.set_property(property, value.as_ref()),
+                    None => style.remove_property(property).map(|_| ()),
+                }
+                .unwrap_throw();
+            }
+        }
+    }
+}
+impl<T: AsRef<web_sys::Element>> ShortcutBind for T {}
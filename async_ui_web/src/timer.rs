@@ -0,0 +1,77 @@
+//! A minimal `setTimeout`-based timer for internal use.
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use async_ui_internal_utils::dummy_waker::dummy_waker;
+use async_ui_web_core::window::WINDOW;
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+
+/// A Future that completes after a delay. The timeout is cleared if it is dropped early.
+pub(crate) struct Sleep {
+    handle: i32,
+    shared: Rc<RefCell<(bool, Waker)>>,
+    _closure: Closure<dyn Fn()>,
+}
+
+/// Start a timer. It starts counting immediately, not on first poll.
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    let shared = Rc::new(RefCell::new((false, dummy_waker())));
+    let shared_weak = Rc::downgrade(&shared);
+    let closure = Closure::new(move || {
+        if let Some(strong) = shared_weak.upgrade() {
+            let (fired, waker) = &mut *strong.borrow_mut();
+            *fired = true;
+            waker.wake_by_ref();
+        }
+        async_ui_web_core::executor::run_now();
+    });
+    let handle = WINDOW
+        .with(|window| {
+            window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                duration.as_millis().try_into().unwrap_or(i32::MAX),
+            )
+        })
+        .unwrap_throw();
+    Sleep {
+        handle,
+        shared,
+        _closure: closure,
+    }
+}
+
+/// The current time in milliseconds, comparable to event timestamps.
+pub(crate) fn now() -> f64 {
+    WINDOW.with(|window| window.performance().unwrap_throw().now())
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (fired, waker) = &mut *self.shared.borrow_mut();
+        if *fired {
+            Poll::Ready(())
+        } else {
+            if !waker.will_wake(cx.waker()) {
+                *waker = cx.waker().to_owned();
+            }
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if !self.shared.borrow().0 {
+            WINDOW.with(|window| window.clear_timeout_with_handle(self.handle));
+        }
+    }
+}
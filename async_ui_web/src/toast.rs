@@ -0,0 +1,368 @@
+//! Toast notifications.
+//!
+//! Any part of the app can [push] a [Toast]; they are shown by the [ToastRegion],
+//! which should be rendered once, usually near the root of the app.
+//!
+//! ```
+//! # use async_ui_web::{toast::{self, Severity, Toast, ToastOutcome, ToastRegion}, join};
+//! # let _ = async {
+//! let region = ToastRegion::new();
+//! join((region.render(), async {
+//!     let outcome = toast::push(
+//!         Toast::new("Message deleted")
+//!             .with_severity(Severity::Success)
+//!             .with_action("Undo"),
+//!     )
+//!     .await;
+//!     if outcome == ToastOutcome::Action(0) {
+//!         // restore the message
+//!     }
+//! }))
+//! .await;
+//! # };
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    future::{pending, Future},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::combinators::{join, race, UiFutureExt};
+use async_ui_web_html::{
+    events::EmitElementEvent,
+    nodes::{Button, Div, Span},
+};
+use futures_lite::StreamExt;
+use wasm_bindgen::UnwrapThrowExt;
+
+use crate::{
+    lists::DynamicList,
+    shortcuts::{ShortcutClassList, ShortcutRenderStr},
+    timer::{now, sleep},
+};
+
+/// How important a [Toast] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Severity {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    /// Announced to screen readers right away, interrupting what they are reading.
+    Error,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "success",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A notification to [push].
+#[derive(Clone, Debug)]
+pub struct Toast {
+    message: String,
+    severity: Severity,
+    timeout: Option<Duration>,
+    actions: Vec<String>,
+}
+
+impl Toast {
+    /// Create a toast with the given message.
+    ///
+    /// It has [Severity::Info], goes away after 5 seconds, and has no action buttons.
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_owned(),
+            severity: Severity::default(),
+            timeout: Some(Duration::from_secs(5)),
+            actions: Vec::new(),
+        }
+    }
+    /// Set the severity.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+    /// Set how long the toast is shown before going away on its own,
+    /// or `None` to keep it until the user closes it.
+    ///
+    /// The time only counts while the toast is on the screen and the
+    /// pointer or the focus isn't in the [ToastRegion].
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Add an action button with the given label.
+    /// Clicking it closes the toast with [ToastOutcome::Action].
+    pub fn with_action(mut self, label: &str) -> Self {
+        self.actions.push(label.to_owned());
+        self
+    }
+}
+
+/// How a toast went away.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastOutcome {
+    /// The user clicked the action button with this index
+    /// (in the order of [with_action][Toast::with_action] calls).
+    Action(usize),
+    /// The user clicked the close button, or [ToastHandle::dismiss] was called.
+    Dismissed,
+    /// The timeout passed.
+    Expired,
+}
+
+struct Entry {
+    id: u64,
+    toast: Toast,
+    outcome: Cell<Option<ToastOutcome>>,
+    waker: RefCell<Option<Waker>>,
+}
+
+thread_local! {
+    /// Toasts pushed but not yet closed, oldest first.
+    static QUEUE: Rc<ReactiveCell<Vec<Rc<Entry>>>> = Rc::new(ReactiveCell::new(Vec::new()));
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+impl Entry {
+    fn finish(self: &Rc<Self>, outcome: ToastOutcome) {
+        if self.outcome.get().is_some() {
+            return;
+        }
+        self.outcome.set(Some(outcome));
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+        QUEUE.with(|queue| queue.borrow_mut().retain(|e| !Rc::ptr_eq(e, self)));
+    }
+}
+
+/// Show a toast.
+///
+/// If more toasts are pushed than the [ToastRegion] shows at once,
+/// this one waits for others to go away.
+///
+/// The returned handle is a Future that completes when the toast goes away.
+/// It doesn't need to be awaited; dropping it leaves the toast up.
+pub fn push(toast: Toast) -> ToastHandle {
+    let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+    let entry = Rc::new(Entry {
+        id,
+        toast,
+        outcome: Cell::new(None),
+        waker: RefCell::new(None),
+    });
+    QUEUE.with(|queue| queue.borrow_mut().push(entry.clone()));
+    ToastHandle { entry }
+}
+
+/// A toast that was [push]ed.
+///
+/// Await this to find out how the toast went away.
+pub struct ToastHandle {
+    entry: Rc<Entry>,
+}
+
+impl ToastHandle {
+    /// Close the toast (or take it out of the queue if it isn't shown yet).
+    pub fn dismiss(&self) {
+        self.entry.finish(ToastOutcome::Dismissed);
+    }
+}
+
+impl Future for ToastHandle {
+    type Output = ToastOutcome;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.entry.outcome.get() {
+            Some(outcome) => Poll::Ready(outcome),
+            None => {
+                let mut waker = self.entry.waker.borrow_mut();
+                if !waker.as_ref().is_some_and(|w| w.will_wake(cx.waker())) {
+                    *waker = Some(cx.waker().to_owned());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/**
+Where [push]ed toasts are shown.
+
+Render exactly one of these. It is a `<div>` with the `toast-region` class
+(each toast inside is a `<div>` with the `toast` class and a `data-severity` attribute);
+use CSS to place it, for example in a corner of the screen with `position: fixed`.
+
+Toasts are shown oldest first, up to a [limit][Self::set_limit].
+Their timeouts are paused while the pointer or the keyboard focus is in the region,
+so they don't go away while the user is reading or reaching for them.
+
+The region is an ARIA live region, so screen readers announce new toasts.
+ */
+pub struct ToastRegion {
+    root: Div,
+    limit: ReactiveCell<usize>,
+    paused: ReactiveCell<bool>,
+}
+
+impl Default for ToastRegion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type ToastFuture<'c> = Pin<Box<dyn Future<Output = ()> + 'c>>;
+
+impl ToastRegion {
+    /// Create a region showing up to 3 toasts at once.
+    pub fn new() -> Self {
+        let root = Div::new();
+        root.add_class("toast-region");
+        root.set_attribute("role", "region").unwrap_throw();
+        root.set_attribute("aria-label", "Notifications")
+            .unwrap_throw();
+        root.set_attribute("aria-live", "polite").unwrap_throw();
+        Self {
+            root,
+            limit: ReactiveCell::new(3),
+            paused: ReactiveCell::new(false),
+        }
+    }
+    /// Set how many toasts are shown at once. The rest wait their turn.
+    pub fn set_limit(&self, limit: usize) {
+        *self.limit.borrow_mut() = limit;
+    }
+    /// Put the region on the screen, and show toasts in it.
+    ///
+    /// This async method never completes.
+    pub async fn render(&self) {
+        let list = DynamicList::<u64, ToastFuture<'_>>::new();
+        self.root
+            .render(list.render())
+            .meanwhile(join((self.track_pause(), self.update(&list))))
+            .await
+    }
+    async fn update<'c>(&'c self, list: &DynamicList<'c, u64, ToastFuture<'c>>) {
+        let queue = QUEUE.with(Rc::clone);
+        loop {
+            let shown: Vec<Rc<Entry>> = queue
+                .borrow()
+                .iter()
+                .take(*self.limit.borrow())
+                .cloned()
+                .collect();
+            list.retain(|id| shown.iter().any(|entry| entry.id == *id));
+            for entry in shown {
+                if !list.contains_key(&entry.id) {
+                    list.insert(entry.id, Box::pin(self.render_toast(entry)), None);
+                }
+            }
+            race((queue.until_change(), self.limit.until_change())).await;
+        }
+    }
+    async fn track_pause(&self) {
+        let mut hovered = false;
+        let mut focused = false;
+        let mut events = self
+            .root
+            .until_pointerenter()
+            .map(|_| (Some(true), None))
+            .or(self.root.until_pointerleave().map(|_| (Some(false), None)))
+            .or(self.root.until_focusin().map(|_| (None, Some(true))))
+            .or(self.root.until_focusout().map(|_| (None, Some(false))));
+        while let Some((hover, focus)) = events.next().await {
+            hovered = hover.unwrap_or(hovered);
+            focused = focus.unwrap_or(focused);
+            let paused = hovered || focused;
+            if *self.paused.borrow() != paused {
+                *self.paused.borrow_mut() = paused;
+            }
+        }
+    }
+    async fn render_toast(&self, entry: Rc<Entry>) {
+        let toast = &entry.toast;
+        let root = Div::new();
+        root.add_class("toast");
+        root.set_attribute("data-severity", toast.severity.as_str())
+            .unwrap_throw();
+        if toast.severity == Severity::Error {
+            root.set_attribute("role", "alert").unwrap_throw();
+        }
+        let actions: Vec<Button> = toast.actions.iter().map(|_| Button::new()).collect();
+        let close = Button::new();
+        close.set_attribute("aria-label", "Close").unwrap_throw();
+        let outcome = race((
+            self.expire(toast.timeout),
+            async {
+                close.until_click().await;
+                ToastOutcome::Dismissed
+            },
+            race(
+                actions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, button)| async move {
+                        button.until_click().await;
+                        ToastOutcome::Action(index)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        ))
+        .meanwhile(
+            root.render(join((
+                Span::new().render(toast.message.render()),
+                join(
+                    actions
+                        .iter()
+                        .zip(&toast.actions)
+                        .map(|(button, label)| button.render(label.render()))
+                        .collect::<Vec<_>>(),
+                ),
+                close.render("×".render()),
+            ))),
+        )
+        .await;
+        entry.finish(outcome);
+    }
+    /// Complete once the toast has been shown (unpaused) for `timeout`.
+    async fn expire(&self, timeout: Option<Duration>) -> ToastOutcome {
+        let Some(mut remaining) = timeout else {
+            return pending().await;
+        };
+        loop {
+            while *self.paused.borrow() {
+                self.paused.until_change().await;
+            }
+            let start = now();
+            let expired = race((
+                async {
+                    sleep(remaining).await;
+                    true
+                },
+                async {
+                    self.paused.until_change().await;
+                    false
+                },
+            ))
+            .await;
+            if expired {
+                return ToastOutcome::Expired;
+            }
+            let elapsed = Duration::from_secs_f64((now() - start).max(0.0) / 1000.0);
+            remaining = remaining.saturating_sub(elapsed);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use std::cell::Cell;
+
+thread_local! {
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// An element ID not used by any other component, such as `"async-ui-tabs-3"`.
+///
+/// Components use these to link their elements with ARIA attributes
+/// (`aria-controls`, `aria-describedby`, and so on).
+pub(crate) fn unique_id(prefix: &str) -> String {
+    let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+    format!("async-ui-{prefix}-{id}")
+}
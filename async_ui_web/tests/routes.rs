@@ -0,0 +1,74 @@
+use async_ui_web::router::Routes;
+
+#[derive(Routes, Clone, Debug, PartialEq)]
+enum Page {
+    #[route("/")]
+    Home,
+    #[route("/users/:id")]
+    User { id: u32 },
+    #[route("/users/:id/posts/:slug")]
+    Post { slug: String, id: u32 },
+    #[route("/archive/:year/:month")]
+    Archive(u16, u8),
+    #[route("/settings/*")]
+    Settings,
+}
+
+#[test]
+fn match_paths() {
+    assert_eq!(Page::from_path("/"), Some(Page::Home));
+    assert_eq!(Page::from_path(""), Some(Page::Home));
+    assert_eq!(Page::from_path("/users/42"), Some(Page::User { id: 42 }));
+    assert_eq!(Page::from_path("/users/42/"), Some(Page::User { id: 42 }));
+    assert_eq!(Page::from_path("/users/abc"), None);
+    assert_eq!(
+        Page::from_path("/users/7/posts/hello%20world"),
+        Some(Page::Post {
+            slug: "hello world".into(),
+            id: 7
+        })
+    );
+    assert_eq!(
+        Page::from_path("/archive/2024/5"),
+        Some(Page::Archive(2024, 5))
+    );
+    assert_eq!(Page::from_path("/archive/2024"), None);
+    assert_eq!(Page::from_path("/nowhere"), None);
+}
+
+#[test]
+fn prefix_routes() {
+    assert_eq!(Page::match_segments(&["settings"]), Some((Page::Settings, 1)));
+    assert_eq!(
+        Page::match_segments(&["settings", "profile", "avatar"]),
+        Some((Page::Settings, 1))
+    );
+    // other routes must match the whole path
+    assert_eq!(Page::match_segments(&["users", "1", "extra"]), None);
+}
+
+#[test]
+fn paths_round_trip() {
+    for page in [
+        Page::Home,
+        Page::User { id: 3 },
+        Page::Post {
+            slug: "a/b c".into(),
+            id: 9,
+        },
+        Page::Archive(1999, 12),
+        Page::Settings,
+    ] {
+        assert_eq!(Page::from_path(&page.to_path()), Some(page.clone()));
+    }
+    assert_eq!(Page::Home.to_path(), "/");
+    assert_eq!(
+        Page::Post {
+            slug: "a/b c".into(),
+            id: 9
+        }
+        .to_path(),
+        "/users/9/posts/a%2Fb%20c"
+    );
+    assert_eq!(Page::Settings.to_path(), "/settings");
+}
@@ -1,90 +1,227 @@
 use crate::events::{EmitEvent, EventFutureStream};
-use web_sys::{Element, HtmlElement};
+use web_sys::{Document, Element, HtmlElement, HtmlFormElement, HtmlMediaElement, Window};
 
 macro_rules! make_event_impl {
-    ($ev_name:literal, $func_name:ident, $ty:ty, $link:tt) => {
-        #[must_use("the returned object is a Future+Stream that does nothing unless polled")]
+    ($ev_name:literal, $func_name:ident, $ty:ty, $interface:literal) => {
+        #[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
         #[doc = "Like [until_event][EmitEvent::until_event] for the `"]
         #[doc = $ev_name]
         #[doc = "` event."]
-        #[doc = "See"]
-        #[doc = $link]
-        #[doc = "."]
+        #[doc = concat!(
+            "See [MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/",
+            $interface, "/", $ev_name, "_event)."
+        )]
         fn $func_name(&self) -> EventFutureStream<$ty> {
             self.as_ref().until_event($ev_name.into())
         }
     };
+    // the entry says MDN documents the event under a different interface
+    ($ev_name:literal, $func_name:ident, $ty:ty, $interface:literal, $override:literal) => {
+        make_event_impl!($ev_name, $func_name, $ty, $override);
+    };
 }
 
-/// Subscribe to common events emitted by HTML `Element`s such as `click` or `scroll`.
+/// Generate an event trait (and its impl for the target type) from a listing.
 ///
-/// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/Element#events).
-#[rustfmt::skip]
-pub trait EmitElementEvent: AsRef<Element> {
-    make_event_impl!("cancel", until_cancel, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mousemove_event)");
-    make_event_impl!("error", until_error, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/error_event)");
-    make_event_impl!("scroll", until_scroll, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/scroll_event)");
-    make_event_impl!("securitypolicyviolation", until_securitypolicyviolation, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/securitypolicyviolation_event)");
-    make_event_impl!("select", until_select, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/select_event)");
-    make_event_impl!("wheel", until_wheel, web_sys::WheelEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/wheel_event)");
-
-    make_event_impl!("compositionend", until_compositionend, web_sys::CompositionEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/compositionend_event)");
-    make_event_impl!("compositionstart", until_compositionstart, web_sys::CompositionEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/compositionstart_event)");
-    make_event_impl!("compositionupdate", until_compositionupdate, web_sys::CompositionEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/compositionupdate_event)");
-
-    make_event_impl!("blur", until_blur, web_sys::UiEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/blur_event)");
-    make_event_impl!("focus", until_focus, web_sys::UiEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/focus_event)");
-    make_event_impl!("focusin", until_focusin, web_sys::UiEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/focusin_event)");
-    make_event_impl!("focusout", until_focusout, web_sys::UiEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/focusout_event)");
-
-    make_event_impl!("fullscreenchange", until_fullscreenchange, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/fullscreenchange_event)");
-    make_event_impl!("fullscreenerror", until_fullscreenerror, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/fullscreenerror_event)");
-
-    make_event_impl!("keydown", until_keydown, web_sys::KeyboardEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/keydown_event)");
-    make_event_impl!("keyup", until_keyup, web_sys::KeyboardEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/keyup_event)");
-
-    make_event_impl!("auxclick", until_auxclick, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/auxclick_event)");
-    make_event_impl!("click", until_click, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/click_event)");
-    make_event_impl!("contextmenu", until_contextmenu, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/contextmenu_event)");
-    make_event_impl!("dblclick", until_dblclick, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/dblclick_event)");
-    make_event_impl!("mousedown", until_mousedown, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mousedown_event)");
-    make_event_impl!("mouseenter", until_mouseenter, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mouseenter_event)");
-    make_event_impl!("mouseleave", until_mouseleave, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mouseleave_event)");
-    make_event_impl!("mousemove", until_mousemove, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mousemove_event)");
-    make_event_impl!("mouseout", until_mouseout, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mouseout_event)");
-    make_event_impl!("mouseover", until_mouseover, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mouseover_event)");
-    make_event_impl!("mouseup", until_mouseup, web_sys::MouseEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/mouseup_event)");
-
-    make_event_impl!("touchcancel", until_touchcancel, web_sys::TouchEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/touchcancel_event)");
-    make_event_impl!("touchend", until_touchend, web_sys::TouchEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/touchend_event)");
-    make_event_impl!("touchmove", until_touchmove, web_sys::TouchEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/touchmove_event)");
-    make_event_impl!("touchstart", until_touchstart, web_sys::TouchEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/touchstart_event)");
-
-    // make_event_impl!("copy", event_copy, web_sys::ClipboardEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/copy_event)");
-    // make_event_impl!("cut", event_cut, web_sys::ClipboardEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/cut_event)");
-    // make_event_impl!("paste", event_paste, web_sys::ClipboardEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/Element/paste_event)");
+/// Each entry is `"event name" => method_name: EventType;`.
+/// MDN links are built from the interface name given after the trait bound.
+/// Entries documented under another interface on MDN say so with
+/// `in "OtherInterface"` after the event name.
+macro_rules! make_event_trait {
+    (
+        $(#[$meta:meta])*
+        pub trait $trait_name:ident: AsRef<$target:ty>, $interface:literal {
+            $($ev_name:literal $(in $override:literal)? => $func_name:ident: $ty:ty;)*
+        }
+    ) => {
+        $(#[$meta])*
+        pub trait $trait_name: AsRef<$target> {
+            $(
+                make_event_impl!($ev_name, $func_name, $ty, $interface $(, $override)?);
+            )*
+        }
+        impl $trait_name for $target {}
+    };
+}
+
+make_event_trait! {
+    /// Subscribe to common events emitted by HTML `Element`s such as `click` or `scroll`.
+    ///
+    /// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/Element#events).
+    pub trait EmitElementEvent: AsRef<Element>, "Element" {
+        "cancel" in "HTMLDialogElement" => until_cancel: web_sys::Event;
+        "error" => until_error: web_sys::Event;
+        "scroll" => until_scroll: web_sys::Event;
+        "scrollend" => until_scrollend: web_sys::Event;
+        "securitypolicyviolation" => until_securitypolicyviolation: web_sys::SecurityPolicyViolationEvent;
+        "select" => until_select: web_sys::Event;
+        "wheel" => until_wheel: web_sys::WheelEvent;
+
+        "animationcancel" => until_animationcancel: web_sys::AnimationEvent;
+        "animationend" => until_animationend: web_sys::AnimationEvent;
+        "animationiteration" => until_animationiteration: web_sys::AnimationEvent;
+        "animationstart" => until_animationstart: web_sys::AnimationEvent;
+
+        "copy" => until_copy: web_sys::ClipboardEvent;
+        "cut" => until_cut: web_sys::ClipboardEvent;
+        "paste" => until_paste: web_sys::ClipboardEvent;
+
+        "compositionend" => until_compositionend: web_sys::CompositionEvent;
+        "compositionstart" => until_compositionstart: web_sys::CompositionEvent;
+        "compositionupdate" => until_compositionupdate: web_sys::CompositionEvent;
+
+        "blur" => until_blur: web_sys::FocusEvent;
+        "focus" => until_focus: web_sys::FocusEvent;
+        "focusin" => until_focusin: web_sys::FocusEvent;
+        "focusout" => until_focusout: web_sys::FocusEvent;
+
+        "fullscreenchange" => until_fullscreenchange: web_sys::Event;
+        "fullscreenerror" => until_fullscreenerror: web_sys::Event;
+
+        "beforeinput" => until_beforeinput: web_sys::InputEvent;
+
+        "keydown" => until_keydown: web_sys::KeyboardEvent;
+        "keyup" => until_keyup: web_sys::KeyboardEvent;
 
+        "auxclick" => until_auxclick: web_sys::MouseEvent;
+        "click" => until_click: web_sys::MouseEvent;
+        "contextmenu" => until_contextmenu: web_sys::MouseEvent;
+        "dblclick" => until_dblclick: web_sys::MouseEvent;
+        "mousedown" => until_mousedown: web_sys::MouseEvent;
+        "mouseenter" => until_mouseenter: web_sys::MouseEvent;
+        "mouseleave" => until_mouseleave: web_sys::MouseEvent;
+        "mousemove" => until_mousemove: web_sys::MouseEvent;
+        "mouseout" => until_mouseout: web_sys::MouseEvent;
+        "mouseover" => until_mouseover: web_sys::MouseEvent;
+        "mouseup" => until_mouseup: web_sys::MouseEvent;
+
+        "gotpointercapture" => until_gotpointercapture: web_sys::PointerEvent;
+        "lostpointercapture" => until_lostpointercapture: web_sys::PointerEvent;
+        "pointercancel" => until_pointercancel: web_sys::PointerEvent;
+        "pointerdown" => until_pointerdown: web_sys::PointerEvent;
+        "pointerenter" => until_pointerenter: web_sys::PointerEvent;
+        "pointerleave" => until_pointerleave: web_sys::PointerEvent;
+        "pointermove" => until_pointermove: web_sys::PointerEvent;
+        "pointerout" => until_pointerout: web_sys::PointerEvent;
+        "pointerover" => until_pointerover: web_sys::PointerEvent;
+        "pointerup" => until_pointerup: web_sys::PointerEvent;
+
+        "touchcancel" => until_touchcancel: web_sys::TouchEvent;
+        "touchend" => until_touchend: web_sys::TouchEvent;
+        "touchmove" => until_touchmove: web_sys::TouchEvent;
+        "touchstart" => until_touchstart: web_sys::TouchEvent;
+
+        "transitioncancel" => until_transitioncancel: web_sys::TransitionEvent;
+        "transitionend" => until_transitionend: web_sys::TransitionEvent;
+        "transitionrun" => until_transitionrun: web_sys::TransitionEvent;
+        "transitionstart" => until_transitionstart: web_sys::TransitionEvent;
+    }
 }
 
-impl EmitElementEvent for Element {}
-
-/// Subscribe to common events emitted by HTML `HTMLElement`s such as `input` or `drag`.
-/// 
-/// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement#events).
-#[rustfmt::skip]
-pub trait EmitHtmlElementEvent: AsRef<HtmlElement> {
-    make_event_impl!("input", until_input, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/input_event)");
-    make_event_impl!("change", until_change, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/change_event)");
-
-    make_event_impl!("drag", until_drag, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/drag_event)");
-    make_event_impl!("dragend", until_dragend, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/dragend_event)");
-    make_event_impl!("dragenter", until_dragenter, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/dragenter_event)");
-    make_event_impl!("dragleave", until_dragleave, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/dragleave_event)");
-    make_event_impl!("dragover", until_dragover, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/dragover_event)");
-    make_event_impl!("dragstart", until_dragstart, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/dragstart_event)");
-    make_event_impl!("drop", until_drop, web_sys::DragEvent, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/drop_event)");
-
-    make_event_impl!("load", until_load, web_sys::Event, "[MDN documentation for this event](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/load_event)");
+make_event_trait! {
+    /// Subscribe to common events emitted by HTML `HTMLElement`s such as `input` or `drag`.
+    ///
+    /// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement#events).
+    pub trait EmitHtmlElementEvent: AsRef<HtmlElement>, "HTMLElement" {
+        "input" => until_input: web_sys::Event;
+        "change" => until_change: web_sys::Event;
+        "invalid" in "HTMLInputElement" => until_invalid: web_sys::Event;
+
+        "beforetoggle" => until_beforetoggle: web_sys::ToggleEvent;
+        "toggle" => until_toggle: web_sys::ToggleEvent;
+
+        "drag" => until_drag: web_sys::DragEvent;
+        "dragend" => until_dragend: web_sys::DragEvent;
+        "dragenter" => until_dragenter: web_sys::DragEvent;
+        "dragleave" => until_dragleave: web_sys::DragEvent;
+        "dragover" => until_dragover: web_sys::DragEvent;
+        "dragstart" => until_dragstart: web_sys::DragEvent;
+        "drop" => until_drop: web_sys::DragEvent;
+
+        "load" => until_load: web_sys::Event;
+    }
 }
 
-impl EmitHtmlElementEvent for HtmlElement {}
+make_event_trait! {
+    /// Subscribe to events emitted by HTML `<form>` elements such as `submit`.
+    ///
+    /// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/HTMLFormElement#events).
+    pub trait EmitFormElementEvent: AsRef<HtmlFormElement>, "HTMLFormElement" {
+        "formdata" => until_formdata: web_sys::Event;
+        "reset" => until_reset: web_sys::Event;
+        "submit" => until_submit: web_sys::SubmitEvent;
+    }
+}
+
+make_event_trait! {
+    /// Subscribe to events emitted by HTML `<audio>` and `<video>` elements
+    /// such as `play` or `timeupdate`.
+    ///
+    /// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/HTMLMediaElement#events).
+    pub trait EmitMediaElementEvent: AsRef<HtmlMediaElement>, "HTMLMediaElement" {
+        "abort" => until_abort: web_sys::Event;
+        "canplay" => until_canplay: web_sys::Event;
+        "canplaythrough" => until_canplaythrough: web_sys::Event;
+        "durationchange" => until_durationchange: web_sys::Event;
+        "emptied" => until_emptied: web_sys::Event;
+        "ended" => until_ended: web_sys::Event;
+        "loadeddata" => until_loadeddata: web_sys::Event;
+        "loadedmetadata" => until_loadedmetadata: web_sys::Event;
+        "loadstart" => until_loadstart: web_sys::Event;
+        "pause" => until_pause: web_sys::Event;
+        "play" => until_play: web_sys::Event;
+        "playing" => until_playing: web_sys::Event;
+        "progress" => until_progress: web_sys::Event;
+        "ratechange" => until_ratechange: web_sys::Event;
+        "seeked" => until_seeked: web_sys::Event;
+        "seeking" => until_seeking: web_sys::Event;
+        "stalled" => until_stalled: web_sys::Event;
+        "suspend" => until_suspend: web_sys::Event;
+        "timeupdate" => until_timeupdate: web_sys::Event;
+        "volumechange" => until_volumechange: web_sys::Event;
+        "waiting" => until_waiting: web_sys::Event;
+    }
+}
+
+make_event_trait! {
+    /// Subscribe to events emitted by the `Window` such as `resize` or `popstate`.
+    ///
+    /// ```
+    /// # use async_ui_web_html::events::EmitWindowEvent;
+    /// # use async_ui_web_core::window::WINDOW;
+    /// # let _ = async {
+    /// let mut resizes = WINDOW.with(|window| window.until_resize());
+    /// loop {
+    ///     (&mut resizes).await;
+    ///     // re-layout
+    /// }
+    /// # };
+    /// ```
+    ///
+    /// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/Window#events).
+    pub trait EmitWindowEvent: AsRef<Window>, "Window" {
+        "beforeunload" => until_beforeunload: web_sys::BeforeUnloadEvent;
+        "hashchange" => until_hashchange: web_sys::HashChangeEvent;
+        "load" => until_load: web_sys::Event;
+        "offline" => until_offline: web_sys::Event;
+        "online" => until_online: web_sys::Event;
+        "pagehide" => until_pagehide: web_sys::PageTransitionEvent;
+        "pageshow" => until_pageshow: web_sys::PageTransitionEvent;
+        "popstate" => until_popstate: web_sys::PopStateEvent;
+        "resize" => until_resize: web_sys::UiEvent;
+        "storage" => until_storage: web_sys::StorageEvent;
+    }
+}
+
+make_event_trait! {
+    /// Subscribe to events emitted by the `Document` such as `visibilitychange`.
+    ///
+    /// See [MDN Web Docs for the events](https://developer.mozilla.org/en-US/docs/Web/API/Document#events).
+    pub trait EmitDocumentEvent: AsRef<Document>, "Document" {
+        "DOMContentLoaded" => until_domcontentloaded: web_sys::Event;
+        "fullscreenchange" => until_fullscreenchange: web_sys::Event;
+        "readystatechange" => until_readystatechange: web_sys::Event;
+        "selectionchange" => until_selectionchange: web_sys::Event;
+        "visibilitychange" => until_visibilitychange: web_sys::Event;
+    }
+}
@@ -0,0 +1,304 @@
+//! Delegated event listening.
+//!
+//! Instead of every [EventFutureStream][crate::events::EventFutureStream]
+//! registering its own listener, delegated streams share one pair of listeners
+//! per event type on the document.
+//! When an event arrives, we walk its propagation path and hand the event to
+//! the streams subscribed on each node, in the same order the browser would.
+//!
+//! The listeners are on the document rather than on the node an app is mounted at.
+//! Mounting happens in `async_ui_web`, which this crate doesn't know about, and a
+//! page may have several apps mounted, with elements (such as popovers) rendered
+//! outside of their mount node. Every mount node is in the document, so listening
+//! there covers all of them with a single listener per event type.
+//!
+//! For every event type we register two listeners on the document:
+//! *   a capturing one, which delivers to capturing subscribers from the
+//!     outermost node inward, and (for events that don't bubble) to
+//!     non-capturing subscribers on the event target itself.
+//! *   a bubbling one, which delivers to non-capturing subscribers from the
+//!     event target outward.
+//!
+//! After each node, the executor is run immediately so that subscribers get a
+//! chance to call `stopPropagation()` before we move on to the next node.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use async_ui_web_core::window::DOCUMENT;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+use web_sys::EventTarget;
+
+use crate::event_handling::Shared;
+
+thread_local! {
+    static REGISTRY: RefCell<HashMap<Cow<'static, str>, Delegation>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.with(|c| {
+        let id = c.get();
+        c.set(id + 1);
+        id
+    })
+}
+
+/// Listeners and subscribers for one event type.
+///
+/// Once created, this is kept for the rest of the page's life.
+/// Removing the listeners when the last subscriber goes away would mean
+/// dropping the closures from inside their own invocation
+/// (the subscriber is often dropped in reaction to the event).
+struct Delegation {
+    _capture_listener: Closure<dyn Fn(web_sys::Event)>,
+    _bubble_listener: Closure<dyn Fn(web_sys::Event)>,
+    /// JS Map from subscribed event targets to their key in `subscribers`.
+    /// JS objects can't be hashed in Rust, so we let the JS Map do the lookup.
+    target_keys: js_sys::Map,
+    subscribers: HashMap<u64, Vec<Subscriber>>,
+}
+
+struct Subscriber {
+    id: u64,
+    capture: bool,
+    shared: Weak<RefCell<Shared>>,
+}
+
+/// A stream's registration with the delegated listeners.
+/// Unsubscribes when dropped.
+pub(crate) struct Subscription {
+    event_name: Cow<'static, str>,
+    target: EventTarget,
+    id: u64,
+}
+
+impl Subscription {
+    pub(crate) fn new(
+        target: &EventTarget,
+        event_name: Cow<'static, str>,
+        capture: bool,
+        shared: Weak<RefCell<Shared>>,
+    ) -> Self {
+        let id = next_id();
+        REGISTRY.with(|reg| {
+            let mut reg = reg.borrow_mut();
+            let delegation = reg
+                .entry(event_name.clone())
+                .or_insert_with(|| Delegation::new(event_name.clone()));
+            let key = match delegation.target_keys.get(target).as_f64() {
+                Some(key) => key as u64,
+                None => {
+                    let key = next_id();
+                    delegation
+                        .target_keys
+                        .set(target, &JsValue::from(key as f64));
+                    key
+                }
+            };
+            delegation
+                .subscribers
+                .entry(key)
+                .or_default()
+                .push(Subscriber {
+                    id,
+                    capture,
+                    shared,
+                });
+        });
+        Self {
+            event_name,
+            target: target.clone(),
+            id,
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        REGISTRY.with(|reg| {
+            let mut reg = reg.borrow_mut();
+            let Some(delegation) = reg.get_mut(&*self.event_name) else {
+                return;
+            };
+            if let Some(key) = delegation.target_keys.get(&self.target).as_f64() {
+                let key = key as u64;
+                if let Some(subs) = delegation.subscribers.get_mut(&key) {
+                    subs.retain(|sub| sub.id != self.id);
+                    if subs.is_empty() {
+                        delegation.subscribers.remove(&key);
+                        delegation.target_keys.delete(&self.target);
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl Delegation {
+    fn new(event_name: Cow<'static, str>) -> Self {
+        let name_capture = event_name.clone();
+        let capture_listener = Closure::new(move |ev: web_sys::Event| {
+            dispatch(&name_capture, ev, true);
+        });
+        let name_bubble = event_name.clone();
+        let bubble_listener = Closure::new(move |ev: web_sys::Event| {
+            dispatch(&name_bubble, ev, false);
+        });
+        DOCUMENT.with(|doc| {
+            doc.add_event_listener_with_callback_and_bool(
+                &event_name,
+                capture_listener.as_ref().unchecked_ref(),
+                true,
+            )
+            .unwrap_throw();
+            doc.add_event_listener_with_callback_and_bool(
+                &event_name,
+                bubble_listener.as_ref().unchecked_ref(),
+                false,
+            )
+            .unwrap_throw();
+        });
+        Self {
+            _capture_listener: capture_listener,
+            _bubble_listener: bubble_listener,
+            target_keys: js_sys::Map::new(),
+            subscribers: HashMap::new(),
+        }
+    }
+}
+
+/// Find the subscribers for the given event name on the given node.
+fn subscribers_at(event_name: &str, target: &JsValue, capture: bool) -> Vec<Rc<RefCell<Shared>>> {
+    REGISTRY.with(|reg| {
+        let reg = reg.borrow();
+        let Some(delegation) = reg.get(event_name) else {
+            return Vec::new();
+        };
+        let Some(key) = delegation.target_keys.get(target).as_f64() else {
+            return Vec::new();
+        };
+        delegation
+            .subscribers
+            .get(&(key as u64))
+            .into_iter()
+            .flatten()
+            .filter(|sub| sub.capture == capture)
+            .filter_map(|sub| sub.shared.upgrade())
+            .collect()
+    })
+}
+
+/// Give the event to subscribers on one node, then let them run.
+/// Returns true if propagation has been stopped.
+fn deliver(event_name: &str, ev: &web_sys::Event, target: &JsValue, capture: bool) -> bool {
+    let subscribers = subscribers_at(event_name, target, capture);
+    if !subscribers.is_empty() {
+        for shared in subscribers {
+            shared.borrow_mut().push(ev.clone());
+        }
+        async_ui_web_core::executor::run_now();
+    }
+    ev.cancel_bubble()
+}
+
+fn dispatch(event_name: &str, ev: web_sys::Event, capture_phase: bool) {
+    let path: Vec<JsValue> = ev.composed_path().iter().collect();
+    visit_path(path.len(), capture_phase, ev.bubbles(), |index, capture| {
+        deliver(event_name, &ev, &path[index], capture)
+    });
+}
+
+/// Call `deliver` with each (index in the propagation path, capture) pair
+/// that the given listener handles, in browser order, until it returns true
+/// (propagation stopped). Index 0 is the event target.
+fn visit_path(
+    path_len: usize,
+    capture_phase: bool,
+    bubbles: bool,
+    mut deliver: impl FnMut(usize, bool) -> bool,
+) {
+    if capture_phase {
+        for index in (0..path_len).rev() {
+            if deliver(index, true) {
+                return;
+            }
+        }
+        if !bubbles && path_len > 0 {
+            // The event won't reach our bubbling listener,
+            // so deliver to the non-capturing subscribers on the target here.
+            deliver(0, false);
+        }
+    } else if bubbles {
+        for index in 0..path_len {
+            if deliver(index, false) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Visits for a whole dispatch (capturing listener, then bubbling listener),
+    /// with propagation stopped at the given visit.
+    fn visits(
+        path_len: usize,
+        bubbles: bool,
+        stop_at: Option<(usize, bool)>,
+    ) -> Vec<(usize, bool)> {
+        let mut visited = Vec::new();
+        let mut stopped = false;
+        for capture_phase in [true, false] {
+            if stopped {
+                break;
+            }
+            visit_path(path_len, capture_phase, bubbles, |index, capture| {
+                visited.push((index, capture));
+                stopped = stop_at == Some((index, capture));
+                stopped
+            });
+        }
+        visited
+    }
+
+    #[test]
+    fn dispatch_order() {
+        // target, parent, document
+        assert_eq!(
+            visits(3, true, None),
+            [
+                (2, true),
+                (1, true),
+                (0, true),
+                (0, false),
+                (1, false),
+                (2, false)
+            ]
+        );
+        // non-bubbling events only reach non-capturing streams on the target
+        assert_eq!(
+            visits(3, false, None),
+            [(2, true), (1, true), (0, true), (0, false)]
+        );
+    }
+
+    #[test]
+    fn stop_propagation() {
+        assert_eq!(visits(3, true, Some((1, true))), [(2, true), (1, true)]);
+        assert_eq!(
+            visits(3, true, Some((0, false))),
+            [(2, true), (1, true), (0, true), (0, false)]
+        );
+        assert_eq!(
+            visits(3, true, Some((1, false))),
+            [(2, true), (1, true), (0, true), (0, false), (1, false)]
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use std::{
     borrow::Cow,
     cell::RefCell,
+    collections::VecDeque,
     future::Future,
+    marker::PhantomData,
+    pin::Pin,
     rc::Rc,
-    task::{Poll, Waker},
+    task::{Context, Poll, Waker},
 };
 
 use async_ui_internal_utils::dummy_waker::dummy_waker;
@@ -11,6 +14,8 @@ use futures_core::Stream;
 use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
 use web_sys::{AddEventListenerOptions, EventTarget};
 
+use crate::event_delegation::Subscription;
+
 /// A struct implementing both [Future] and [Stream].
 /// Yields [Event][web_sys::Event] objects.
 ///
@@ -20,16 +25,113 @@ use web_sys::{AddEventListenerOptions, EventTarget};
 /// ### Notes for the Stream API
 ///
 /// *   The returned Stream is never exhausted.
-/// *   The implementation only keeps the last event it receives.
+/// *   By default, the implementation only keeps the last event it receives.
 ///     This means if you use some custom manually-implemented wrapper futures and
-///     fail to poll the Stream upon `wake`, you might miss some
-///     in-between events.
+///     fail to poll the Stream upon `wake`, or if your code is busy doing
+///     something else between events, you might miss some in-between events.
+///     Use [set_buffer][Self::set_buffer] to keep more events.
 pub struct EventFutureStream<E> {
     target: EventTarget,
-    closure: Option<Closure<dyn Fn(web_sys::Event)>>,
-    shared: Rc<RefCell<(Option<E>, Waker)>>,
+    listener: Listener,
+    shared: Rc<RefCell<Shared>>,
     options: Option<AddEventListenerOptions>,
+    capture: bool,
+    delegated: bool,
     event_name: Cow<'static, str>,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+/// How the stream is hooked up to the browser.
+enum Listener {
+    /// Not registered yet; we register on first poll.
+    None,
+    /// Our own `addEventListener` on the target.
+    Direct(Closure<dyn Fn(web_sys::Event)>),
+    /// Registered with the shared listeners on the document.
+    Delegated { _subscription: Subscription },
+}
+
+/// What a buffered [EventFutureStream] should do with a new event when its
+/// buffer is already full.
+///
+/// See [set_buffer][EventFutureStream::set_buffer].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum OverflowPolicy {
+    /// Discard the oldest event in the buffer to make room for the new one.
+    DropOldest,
+    /// Discard the new event, keeping the buffer as is.
+    DropNewest,
+    /// Replace the newest event in the buffer with the new one.
+    ///
+    /// Good for events like `pointermove` where only the latest position
+    /// matters once the consumer falls behind, but the events before
+    /// (and the ordering relative to them) should still be kept.
+    Coalesce,
+}
+
+/// State shared between the stream and the listener.
+///
+/// Generic over the event type only so the buffering can be tested off the browser.
+pub(crate) struct Shared<T = web_sys::Event> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    waker: Waker,
+}
+
+impl<T> Shared<T> {
+    fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            capacity: 1,
+            overflow: OverflowPolicy::DropOldest,
+            waker: dummy_waker(),
+        }
+    }
+    /// Store an event for the stream to yield and wake the stream.
+    pub(crate) fn push(&mut self, ev: T) {
+        if self.queue.len() < self.capacity {
+            self.queue.push_back(ev);
+        } else {
+            match self.overflow {
+                OverflowPolicy::DropOldest => {
+                    self.queue.pop_front();
+                    self.queue.push_back(ev);
+                }
+                OverflowPolicy::DropNewest => {}
+                OverflowPolicy::Coalesce => {
+                    if let Some(last) = self.queue.back_mut() {
+                        *last = ev;
+                    }
+                }
+            }
+        }
+        self.waker.wake_by_ref();
+    }
+    /// Change the capacity and overflow policy, discarding events that no longer fit.
+    fn set_buffer(&mut self, capacity: usize, overflow: OverflowPolicy) {
+        assert!(capacity > 0, "buffer capacity must be at least 1");
+        self.capacity = capacity;
+        self.overflow = overflow;
+        self.trim();
+    }
+    /// Shrink the buffer down to capacity, discarding according to the overflow policy.
+    fn trim(&mut self) {
+        while self.queue.len() > self.capacity {
+            match self.overflow {
+                OverflowPolicy::DropOldest => {
+                    self.queue.pop_front();
+                }
+                OverflowPolicy::DropNewest => {
+                    self.queue.pop_back();
+                }
+                OverflowPolicy::Coalesce => {
+                    let last = self.queue.pop_back().unwrap();
+                    *self.queue.back_mut().unwrap() = last;
+                }
+            }
+        }
+    }
 }
 
 impl<E: JsCast> EventFutureStream<E> {
@@ -38,10 +140,13 @@ impl<E: JsCast> EventFutureStream<E> {
     pub fn new(target: EventTarget, event_name: Cow<'static, str>) -> Self {
         Self {
             target,
-            closure: None,
-            shared: Rc::new(RefCell::new((None, dummy_waker()))),
+            listener: Listener::None,
+            shared: Rc::new(RefCell::new(Shared::new())),
             options: None,
+            capture: false,
+            delegated: false,
             event_name,
+            _phantom: PhantomData,
         }
     }
     /// The `capture` option indicates that that events of this type will be
@@ -53,6 +158,7 @@ impl<E: JsCast> EventFutureStream<E> {
     ///
     /// This needs to be set *before* you first poll the stream.
     pub fn set_capture(&mut self, capture: bool) {
+        self.capture = capture;
         self.options
             .get_or_insert_with(AddEventListenerOptions::new)
             .capture(capture);
@@ -72,6 +178,76 @@ impl<E: JsCast> EventFutureStream<E> {
             .get_or_insert_with(AddEventListenerOptions::new)
             .passive(passive);
     }
+    /// Listen through a shared listener on the document instead of adding
+    /// a listener to the target.
+    ///
+    /// With delegation, there is only one listener per event type no matter
+    /// how many streams are listening. This is worthwhile when you have many
+    /// elements listening for the same event, such as every row of a long list
+    /// waiting for `click`.
+    /// If not specified, defaults to false.
+    ///
+    /// Events are still handed to streams in browser order (capturing streams
+    /// from the outside in, then non-capturing streams from the target out),
+    /// and calling [stop_propagation][web_sys::Event::stop_propagation]
+    /// in reaction to an event stops it from reaching the streams further along.
+    ///
+    /// Some differences from non-delegated listening:
+    /// *   [current_target][web_sys::Event::current_target] of the yielded event
+    ///     is the document rather than the element you are listening on.
+    /// *   The target must be in the document when the event fires.
+    /// *   Non-delegated listeners that stop propagation before the event
+    ///     reaches the document will prevent delegated streams from seeing the
+    ///     event.
+    /// *   [set_passive][Self::set_passive] has no effect.
+    ///
+    /// This needs to be set *before* you first poll the stream.
+    pub fn set_delegated(&mut self, delegated: bool) {
+        self.delegated = delegated;
+    }
+    /// Keep up to `capacity` events that have been received but not yet
+    /// yielded, instead of just the last one.
+    ///
+    /// When an event arrives while the buffer is full, `overflow` decides
+    /// which event gets discarded. See [OverflowPolicy].
+    ///
+    /// Use this when you need every intermediate event, for example every
+    /// `keydown` in a key logger or every `pointermove` in a drawing app,
+    /// even if your code is busy when some of them arrive.
+    /// Pass [usize::MAX] as the capacity for an unbounded buffer.
+    ///
+    /// By default, the capacity is 1 with [OverflowPolicy::DropOldest]:
+    /// the stream only keeps the last event.
+    ///
+    /// ```
+    /// # use async_ui_web_html::{events::{EmitElementEvent, OverflowPolicy}, nodes::Input};
+    /// # use futures_lite::StreamExt;
+    /// # let _ = async {
+    /// # let input = Input::new();
+    /// let mut keys = input.until_keydown();
+    /// keys.set_buffer(256, OverflowPolicy::DropOldest);
+    /// while let Some(ev) = keys.next().await {
+    ///     // every key is seen, even if this loop body awaits something slow
+    /// }
+    /// # };
+    /// ```
+    ///
+    /// **Panics** if `capacity` is 0.
+    pub fn set_buffer(&mut self, capacity: usize, overflow: OverflowPolicy) {
+        self.shared.borrow_mut().set_buffer(capacity, overflow);
+    }
+}
+
+impl<E: JsCast + 'static> EventFutureStream<E> {
+    /// Add the DOM listener now rather than on the first poll,
+    /// so events fired before then are kept for it.
+    pub(crate) fn listen_now(mut self) -> Self {
+        let waker = dummy_waker();
+        let mut cx = Context::from_waker(&waker);
+        // the first poll only adds the listener
+        let _ = Pin::new(&mut self).poll_next(&mut cx);
+        self
+    }
 }
 
 impl<E: JsCast + 'static> Future for EventFutureStream<E> {
@@ -96,18 +272,27 @@ impl<E: JsCast + 'static> Stream for EventFutureStream<E> {
         {
             let shared = &mut *this.shared.borrow_mut();
             let waker = cx.waker();
-            if !shared.1.will_wake(waker) {
-                shared.1 = waker.to_owned();
+            if !shared.waker.will_wake(waker) {
+                shared.waker = waker.to_owned();
             }
         }
 
-        if this.closure.is_none() {
+        if let Listener::None = this.listener {
             let shared_weak = Rc::downgrade(&this.shared);
+            if this.delegated {
+                this.listener = Listener::Delegated {
+                    _subscription: Subscription::new(
+                        &this.target,
+                        this.event_name.clone(),
+                        this.capture,
+                        shared_weak,
+                    ),
+                };
+                return Poll::Pending;
+            }
             let closure = Closure::new(move |ev: web_sys::Event| {
                 if let Some(strong) = shared_weak.upgrade() {
-                    let inner = &mut *strong.borrow_mut();
-                    inner.0 = Some(ev.unchecked_into());
-                    inner.1.wake_by_ref();
+                    strong.borrow_mut().push(ev);
                 }
                 async_ui_web_core::executor::run_now();
             });
@@ -125,10 +310,10 @@ impl<E: JsCast + 'static> Stream for EventFutureStream<E> {
                     .add_event_listener_with_callback(&this.event_name, listener)
                     .unwrap_throw();
             }
-            this.closure = Some(closure);
+            this.listener = Listener::Direct(closure);
             Poll::Pending
-        } else if let Some(ev) = this.shared.borrow_mut().0.take() {
-            Poll::Ready(Some(ev))
+        } else if let Some(ev) = this.shared.borrow_mut().queue.pop_front() {
+            Poll::Ready(Some(ev.unchecked_into()))
         } else {
             Poll::Pending
         }
@@ -158,13 +343,81 @@ impl EmitEvent for EventTarget {
 
 impl<E> Drop for EventFutureStream<E> {
     fn drop(&mut self) {
-        if let Some(callback) = self.closure.take() {
+        if let Listener::Direct(callback) = &self.listener {
             self.target
-                .remove_event_listener_with_callback(
+                .remove_event_listener_with_callback_and_bool(
                     &self.event_name,
                     callback.as_ref().unchecked_ref(),
+                    self.capture,
                 )
                 .unwrap_throw();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(capacity: usize, overflow: OverflowPolicy, events: &[u32]) -> Shared<u32> {
+        let mut shared = Shared::new();
+        shared.set_buffer(capacity, overflow);
+        for &ev in events {
+            shared.push(ev);
+        }
+        shared
+    }
+
+    #[test]
+    fn overflow_policies() {
+        let queued = |shared: Shared<u32>| shared.queue.into_iter().collect::<Vec<_>>();
+        // the default keeps only the last event
+        let mut shared = Shared::new();
+        [1, 2, 3].into_iter().for_each(|ev| shared.push(ev));
+        assert_eq!(queued(shared), [3]);
+        let events = [1, 2, 3, 4, 5];
+        assert_eq!(
+            queued(buffer(3, OverflowPolicy::DropOldest, &events)),
+            [3, 4, 5]
+        );
+        assert_eq!(
+            queued(buffer(3, OverflowPolicy::DropNewest, &events)),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            queued(buffer(3, OverflowPolicy::Coalesce, &events)),
+            [1, 2, 5]
+        );
+        assert_eq!(
+            queued(buffer(usize::MAX, OverflowPolicy::DropNewest, &events)),
+            events
+        );
+    }
+
+    #[test]
+    fn shrink_while_queued() {
+        let queued = |shared: &Shared<u32>| shared.queue.iter().copied().collect::<Vec<_>>();
+        let events = [1, 2, 3, 4, 5];
+        let mut shared = buffer(5, OverflowPolicy::DropOldest, &events);
+        shared.set_buffer(2, OverflowPolicy::DropOldest);
+        assert_eq!(queued(&shared), [4, 5]);
+        let mut shared = buffer(5, OverflowPolicy::DropOldest, &events);
+        shared.set_buffer(2, OverflowPolicy::DropNewest);
+        assert_eq!(queued(&shared), [1, 2]);
+        let mut shared = buffer(5, OverflowPolicy::DropOldest, &events);
+        shared.set_buffer(2, OverflowPolicy::Coalesce);
+        assert_eq!(queued(&shared), [1, 5]);
+        shared.set_buffer(1, OverflowPolicy::Coalesce);
+        assert_eq!(queued(&shared), [5]);
+        // growing keeps what is there
+        shared.set_buffer(4, OverflowPolicy::DropOldest);
+        shared.push(6);
+        assert_eq!(queued(&shared), [5, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer capacity must be at least 1")]
+    fn zero_capacity() {
+        Shared::<u32>::new().set_buffer(0, OverflowPolicy::DropOldest);
+    }
+}
@@ -0,0 +1,450 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::Display,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use async_ui_internal_utils::dummy_waker::dummy_waker;
+use futures_core::Stream;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+use web_sys::{DragEvent, EventTarget, File, FileList, FileReader, ProgressEvent};
+
+/// A file picked by the user, from a file [Input][crate::nodes::Input] or a drop.
+///
+/// Use [read_text][Self::read_text] or [read_bytes][Self::read_bytes] to get its content.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileHandle {
+    file: File,
+}
+
+impl From<File> for FileHandle {
+    fn from(file: File) -> Self {
+        Self { file }
+    }
+}
+
+impl FileHandle {
+    /// The name of the file, without the path.
+    pub fn name(&self) -> String {
+        self.file.name()
+    }
+    /// The size of the file, in bytes.
+    pub fn size(&self) -> u64 {
+        self.file.size() as u64
+    }
+    /// The MIME type of the file as guessed by the browser, such as `"image/png"`.
+    /// Empty if unknown.
+    pub fn mime_type(&self) -> String {
+        self.file.type_()
+    }
+    /// When the file was last modified, in milliseconds since the Unix epoch.
+    pub fn last_modified(&self) -> f64 {
+        self.file.last_modified()
+    }
+    /// The underlying [File].
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+    /// Read the content of the file as UTF-8 text.
+    ///
+    /// The read starts right away, and is cancelled if the returned [FileRead] is dropped.
+    pub fn read_text(&self) -> FileRead<String> {
+        FileRead::start(&self.file, |reader, file| reader.read_as_text(file))
+    }
+    /// Read the content of the file as bytes.
+    ///
+    /// The read starts right away, and is cancelled if the returned [FileRead] is dropped.
+    pub fn read_bytes(&self) -> FileRead<Vec<u8>> {
+        FileRead::start(&self.file, |reader, file| reader.read_as_array_buffer(file))
+    }
+}
+
+fn collect_files(list: Option<FileList>) -> Vec<FileHandle> {
+    let Some(list) = list else {
+        return Vec::new();
+    };
+    (0..list.length())
+        .filter_map(|i| list.get(i))
+        .map(FileHandle::from)
+        .collect()
+}
+
+/// Get the files dropped in a `drop` event, such as one from
+/// [until_drop][crate::events::EmitHtmlElementEvent::until_drop].
+///
+/// Returns an empty list if the drop didn't carry any files (for example, dropped text).
+/// To be able to drop on an element at all, its `dragover` events must be
+/// [prevented][web_sys::Event::prevent_default]; [until_file_drop] does that for you.
+pub fn dropped_files(ev: &DragEvent) -> Vec<FileHandle> {
+    collect_files(ev.data_transfer().and_then(|dt| dt.files()))
+}
+
+/// Make the given element accept dropped files, and yield the files of each drop.
+///
+/// ```
+/// # use async_ui_web_html::{files::until_file_drop, nodes::Div};
+/// # use futures_lite::StreamExt;
+/// # let _ = async {
+/// let zone = Div::new();
+/// let mut drops = until_file_drop(&zone);
+/// while let Some(files) = drops.next().await {
+///     for file in files {
+///         let content = file.read_text().await;
+///     }
+/// }
+/// # };
+/// ```
+///
+/// Drops that carry no files are skipped.
+///
+/// Both events are cancelled as soon as they fire, even while your code is busy
+/// with a previous drop (like the `read_text` above). Drops that arrive meanwhile
+/// are kept until you ask for them.
+pub fn until_file_drop(target: &impl AsRef<EventTarget>) -> FileDrops {
+    let target = target.as_ref().to_owned();
+    let shared = Rc::new(RefCell::new(DropShared {
+        drops: VecDeque::new(),
+        waker: dummy_waker(),
+    }));
+    let closure = {
+        let shared = shared.clone();
+        Closure::new(move |ev: DragEvent| {
+            // the default for dragover is to refuse the drop; for drop, to open the file
+            ev.prevent_default();
+            if ev.type_() != "drop" {
+                return;
+            }
+            let files = dropped_files(&ev);
+            if files.is_empty() {
+                return;
+            }
+            {
+                let mut shared = shared.borrow_mut();
+                shared.drops.push_back(files);
+                shared.waker.wake_by_ref();
+            }
+            async_ui_web_core::executor::run_now();
+        })
+    };
+    for name in DROP_EVENTS {
+        target
+            .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+            .unwrap_throw();
+    }
+    FileDrops {
+        target,
+        shared,
+        closure,
+    }
+}
+
+const DROP_EVENTS: [&str; 2] = ["dragover", "drop"];
+
+struct DropShared {
+    drops: VecDeque<Vec<FileHandle>>,
+    waker: Waker,
+}
+
+/// The Stream returned by [until_file_drop].
+///
+/// The element stops accepting drops when this is dropped.
+pub struct FileDrops {
+    target: EventTarget,
+    shared: Rc<RefCell<DropShared>>,
+    closure: Closure<dyn Fn(DragEvent)>,
+}
+
+impl Stream for FileDrops {
+    type Item = Vec<FileHandle>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.borrow_mut();
+        match shared.drops.pop_front() {
+            Some(files) => Poll::Ready(Some(files)),
+            None => {
+                if !shared.waker.will_wake(cx.waker()) {
+                    shared.waker = cx.waker().to_owned();
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for FileDrops {
+    fn drop(&mut self) {
+        for name in DROP_EVENTS {
+            self.target
+                .remove_event_listener_with_callback(name, self.closure.as_ref().unchecked_ref())
+                .ok();
+        }
+    }
+}
+
+impl super::nodes::Input {
+    /// The files currently selected in this input.
+    /// Create the input with [new_file][Self::new_file].
+    ///
+    /// Empty if nothing is selected, or if this is not a file input.
+    /// Wait for the user to pick files with
+    /// [until_change][crate::events::EmitHtmlElementEvent::until_change].
+    pub fn selected_files(&self) -> Vec<FileHandle> {
+        collect_files(self.element.files())
+    }
+}
+
+/// How much of a file has been read so far.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadProgress {
+    /// Bytes read.
+    pub loaded: u64,
+    /// Bytes in total, if known.
+    pub total: Option<u64>,
+}
+
+/// Why reading a file failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileReadError {
+    /// The file doesn't exist anymore.
+    NotFound,
+    /// The file couldn't be read, for example because of permissions,
+    /// or because it changed since it was picked.
+    NotReadable,
+    /// The content isn't of the requested kind.
+    /// Currently this can only happen with [read_text][FileHandle::read_text].
+    InvalidContent,
+    /// Any other error, with its message.
+    Other(String),
+}
+
+impl Display for FileReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("the file was not found"),
+            Self::NotReadable => f.write_str("the file could not be read"),
+            Self::InvalidContent => f.write_str("the file content is not valid"),
+            Self::Other(message) => write!(f, "reading the file failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for FileReadError {}
+
+impl From<JsValue> for FileReadError {
+    fn from(value: JsValue) -> Self {
+        let get = |key: &str| {
+            js_sys::Reflect::get(&value, &key.into())
+                .ok()
+                .and_then(|v| v.as_string())
+        };
+        match get("name").as_deref() {
+            Some("NotFoundError") => Self::NotFound,
+            Some("NotReadableError") => Self::NotReadable,
+            _ => Self::Other(get("message").unwrap_or_else(|| format!("{value:?}"))),
+        }
+    }
+}
+
+/// The content types a [FileRead] can produce.
+pub trait FileContent: Sized {
+    #[doc(hidden)]
+    fn from_result(result: JsValue) -> Result<Self, FileReadError>;
+}
+
+impl FileContent for String {
+    fn from_result(result: JsValue) -> Result<Self, FileReadError> {
+        result.as_string().ok_or(FileReadError::InvalidContent)
+    }
+}
+
+impl FileContent for Vec<u8> {
+    fn from_result(result: JsValue) -> Result<Self, FileReadError> {
+        let buffer: js_sys::ArrayBuffer = result
+            .dyn_into()
+            .map_err(|_| FileReadError::InvalidContent)?;
+        Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+    }
+}
+
+const READER_EVENTS: [&str; 3] = ["progress", "load", "error"];
+
+struct Shared {
+    outcome: Option<Result<JsValue, FileReadError>>,
+    /// Set when the read has finished, failed, or been cancelled.
+    done: bool,
+    progress: Option<ReadProgress>,
+    waker: Waker,
+    progress_waker: Waker,
+}
+
+/// A Future for the content of a file, returned by
+/// [read_text][FileHandle::read_text] and [read_bytes][FileHandle::read_bytes].
+///
+/// Dropping it before it completes cancels the read.
+///
+/// ```
+/// # use async_ui_web_html::{nodes::Input, events::EmitHtmlElementEvent};
+/// # use async_ui_web_core::combinators::join;
+/// # use futures_lite::StreamExt;
+/// # let _ = async {
+/// let input = Input::new_file();
+/// input.until_change().await;
+/// if let Some(file) = input.selected_files().first() {
+///     let read = file.read_bytes();
+///     let mut progress = read.progress();
+///     let (bytes, _) = join((read, async {
+///         while let Some(p) = progress.next().await {
+///             // show p.loaded out of p.total
+///         }
+///     }))
+///     .await;
+/// }
+/// # };
+/// ```
+#[must_use = "the read is cancelled if the FileRead is dropped"]
+pub struct FileRead<T> {
+    reader: FileReader,
+    shared: Rc<RefCell<Shared>>,
+    _closure: Closure<dyn Fn(ProgressEvent)>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: FileContent> FileRead<T> {
+    fn start(file: &File, read: impl FnOnce(&FileReader, &File) -> Result<(), JsValue>) -> Self {
+        let reader = FileReader::new().unwrap_throw();
+        let shared = Rc::new(RefCell::new(Shared {
+            outcome: None,
+            done: false,
+            progress: None,
+            waker: dummy_waker(),
+            progress_waker: dummy_waker(),
+        }));
+        let closure = {
+            let shared = shared.clone();
+            let reader = reader.clone();
+            Closure::new(move |ev: ProgressEvent| {
+                {
+                    let mut shared = shared.borrow_mut();
+                    let shared = &mut *shared;
+                    if let "progress" | "load" = &*ev.type_() {
+                        shared.progress = Some(ReadProgress {
+                            loaded: ev.loaded() as u64,
+                            total: ev.length_computable().then_some(ev.total() as u64),
+                        });
+                        shared.progress_waker.wake_by_ref();
+                    }
+                    let outcome = match &*ev.type_() {
+                        "load" => Some(reader.result().map_err(Into::into)),
+                        "error" => Some(Err(reader
+                            .error()
+                            .map(|e| JsValue::from(e).into())
+                            .unwrap_or(FileReadError::NotReadable))),
+                        _ => None,
+                    };
+                    if let Some(outcome) = outcome {
+                        shared.outcome = Some(outcome);
+                        shared.done = true;
+                        shared.waker.wake_by_ref();
+                        // progress streams end with the read
+                        shared.progress_waker.wake_by_ref();
+                    }
+                }
+                async_ui_web_core::executor::run_now();
+            })
+        };
+        for name in READER_EVENTS {
+            reader
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .unwrap_throw();
+        }
+        if let Err(e) = read(&reader, file) {
+            let mut shared = shared.borrow_mut();
+            shared.outcome = Some(Err(e.into()));
+            shared.done = true;
+        }
+        Self {
+            reader,
+            shared,
+            _closure: closure,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> FileRead<T> {
+    /// A Stream of the progress of this read.
+    /// It ends when the read completes, fails, or is cancelled.
+    ///
+    /// Only the latest progress is kept, so a slow consumer may skip some.
+    pub fn progress(&self) -> ProgressStream {
+        ProgressStream {
+            shared: self.shared.clone(),
+            last: None,
+        }
+    }
+}
+
+impl<T: FileContent> Future for FileRead<T> {
+    type Output = Result<T, FileReadError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.borrow_mut();
+        match shared.outcome.take() {
+            Some(outcome) => Poll::Ready(outcome.and_then(T::from_result)),
+            None => {
+                if !shared.waker.will_wake(cx.waker()) {
+                    shared.waker = cx.waker().to_owned();
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for FileRead<T> {
+    fn drop(&mut self) {
+        // The closure is about to be freed, so stop the reader from calling it.
+        for name in READER_EVENTS {
+            self.reader
+                .remove_event_listener_with_callback(name, self._closure.as_ref().unchecked_ref())
+                .ok();
+        }
+        if self.reader.ready_state() == FileReader::LOADING {
+            self.reader.abort();
+        }
+        let mut shared = self.shared.borrow_mut();
+        shared.done = true;
+        shared.progress_waker.wake_by_ref();
+    }
+}
+
+/// The Stream returned by [FileRead::progress].
+pub struct ProgressStream {
+    shared: Rc<RefCell<Shared>>,
+    last: Option<ReadProgress>,
+}
+
+impl Stream for ProgressStream {
+    type Item = ReadProgress;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let mut shared = this.shared.borrow_mut();
+        if shared.progress.is_some() && shared.progress != this.last {
+            this.last = shared.progress;
+            return Poll::Ready(this.last);
+        }
+        if shared.done {
+            return Poll::Ready(None);
+        }
+        if !shared.progress_waker.will_wake(cx.waker()) {
+            shared.progress_waker = cx.waker().to_owned();
+        }
+        Poll::Pending
+    }
+}
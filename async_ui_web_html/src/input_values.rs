@@ -0,0 +1,478 @@
+use std::{fmt::Display, str::FromStr};
+
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
+use async_ui_web_core::combinators::join;
+use futures_core::Stream;
+use futures_lite::StreamExt;
+use web_sys::{EventTarget, HtmlInputElement};
+
+use crate::events::EmitEvent;
+
+/// A type that can be read from and written to an [Input][super::nodes::Input].
+///
+/// Implemented for
+/// *   [bool], the `checked` state (for checkboxes and radio buttons),
+/// *   [f64], for `number` and `range` inputs,
+/// *   [String], the raw value (for text-like inputs),
+/// *   [InputDate] and [InputTime], for `date` and `time` inputs.
+pub trait InputValue: Sized {
+    /// The event fired when the user changes this value.
+    const EVENT: &'static str = "input";
+    /// Read the current value of the input.
+    fn read(input: &HtmlInputElement) -> Result<Self, InputValueError>;
+    /// Set the value of the input.
+    fn write(&self, input: &HtmlInputElement);
+}
+
+/// Why the value of an [Input][super::nodes::Input] couldn't be read as the requested type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputValueError {
+    /// The input is empty.
+    Empty,
+    /// The user entered something the browser itself couldn't make sense of,
+    /// for example letters in a `number` input.
+    /// Browsers report the value as empty in this case, so we can't show what was entered.
+    BadInput,
+    /// The value couldn't be parsed as the requested type.
+    Invalid(String),
+}
+
+impl Display for InputValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the input is empty"),
+            Self::BadInput => f.write_str("the input contains an invalid value"),
+            Self::Invalid(value) => write!(f, "invalid input value {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for InputValueError {}
+
+/// Read the value string and parse it with [FromStr].
+fn read_parsed<T: FromStr>(input: &HtmlInputElement) -> Result<T, InputValueError> {
+    let value = input.value();
+    if value.is_empty() {
+        if input.validity().bad_input() {
+            Err(InputValueError::BadInput)
+        } else {
+            Err(InputValueError::Empty)
+        }
+    } else {
+        value
+            .trim()
+            .parse()
+            .map_err(|_| InputValueError::Invalid(value))
+    }
+}
+
+impl InputValue for bool {
+    const EVENT: &'static str = "change";
+    fn read(input: &HtmlInputElement) -> Result<Self, InputValueError> {
+        Ok(input.checked())
+    }
+    fn write(&self, input: &HtmlInputElement) {
+        input.set_checked(*self);
+    }
+}
+
+impl InputValue for f64 {
+    fn read(input: &HtmlInputElement) -> Result<Self, InputValueError> {
+        read_parsed(input)
+    }
+    fn write(&self, input: &HtmlInputElement) {
+        input.set_value(&self.to_string());
+    }
+}
+
+impl InputValue for String {
+    fn read(input: &HtmlInputElement) -> Result<Self, InputValueError> {
+        Ok(input.value())
+    }
+    fn write(&self, input: &HtmlInputElement) {
+        input.set_value(self);
+    }
+}
+
+impl InputValue for InputDate {
+    fn read(input: &HtmlInputElement) -> Result<Self, InputValueError> {
+        read_parsed(input)
+    }
+    fn write(&self, input: &HtmlInputElement) {
+        input.set_value(&self.to_string());
+    }
+}
+
+impl InputValue for InputTime {
+    fn read(input: &HtmlInputElement) -> Result<Self, InputValueError> {
+        read_parsed(input)
+    }
+    fn write(&self, input: &HtmlInputElement) {
+        input.set_value(&self.to_string());
+    }
+}
+
+/// A calendar date, as used by `<input type="date">`.
+///
+/// Parses from and formats to the `YYYY-MM-DD` format the input uses.
+///
+/// ```
+/// # use async_ui_web_html::nodes::InputDate;
+/// let date: InputDate = "2024-02-29".parse().unwrap();
+/// assert_eq!(date, InputDate { year: 2024, month: 2, day: 29 });
+/// assert_eq!(date.to_string(), "2024-02-29");
+/// assert!("2023-02-29".parse::<InputDate>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InputDate {
+    pub year: i32,
+    /// From 1 to 12.
+    pub month: u8,
+    /// From 1 to the number of days in the month.
+    pub day: u8,
+}
+
+/// A time of day, as used by `<input type="time">`.
+///
+/// Parses from and formats to the `HH:MM[:SS[.mmm]]` format the input uses.
+///
+/// ```
+/// # use async_ui_web_html::nodes::InputTime;
+/// let time: InputTime = "13:45".parse().unwrap();
+/// assert_eq!(time, InputTime { hour: 13, minute: 45, second: 0, millisecond: 0 });
+/// assert_eq!(time.to_string(), "13:45");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InputTime {
+    /// From 0 to 23.
+    pub hour: u8,
+    /// From 0 to 59.
+    pub minute: u8,
+    /// From 0 to 59.
+    pub second: u8,
+    /// From 0 to 999.
+    pub millisecond: u16,
+}
+
+/// The error from parsing an [InputDate] or [InputTime].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseInputValueError;
+
+impl Display for ParseInputValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid date or time")
+    }
+}
+
+impl std::error::Error for ParseInputValueError {}
+
+/// Parse a string of exactly `len` ASCII digits.
+fn parse_digits<T: FromStr>(s: &str, len: usize) -> Result<T, ParseInputValueError> {
+    if s.len() == len && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().map_err(|_| ParseInputValueError)
+    } else {
+        Err(ParseInputValueError)
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for InputDate {
+    type Err = ParseInputValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('-');
+        let (Some(year), Some(month), Some(day), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ParseInputValueError);
+        };
+        // years have at least 4 digits
+        let year = parse_digits(year, year.len().max(4))?;
+        let month = parse_digits(month, 2)?;
+        let day = parse_digits(day, 2)?;
+        if year > 0 && (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)
+        {
+            Ok(Self { year, month, day })
+        } else {
+            Err(ParseInputValueError)
+        }
+    }
+}
+
+impl Display for InputDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for InputTime {
+    type Err = ParseInputValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hm, rest) = match s.len() {
+            5 => (s, None),
+            _ => (s.get(..5).ok_or(ParseInputValueError)?, Some(&s[5..])),
+        };
+        let (hour, minute) = hm.split_once(':').ok_or(ParseInputValueError)?;
+        let hour = parse_digits(hour, 2)?;
+        let minute = parse_digits(minute, 2)?;
+        let (second, millisecond) = match rest {
+            None => (0, 0),
+            Some(rest) => {
+                let rest = rest.strip_prefix(':').ok_or(ParseInputValueError)?;
+                match rest.split_once('.') {
+                    None => (parse_digits(rest, 2)?, 0),
+                    Some((second, fraction)) => {
+                        if fraction.is_empty() || fraction.len() > 3 {
+                            return Err(ParseInputValueError);
+                        }
+                        let millisecond: u16 = parse_digits(fraction, fraction.len())?;
+                        // ".5" is 500 milliseconds
+                        (
+                            parse_digits(second, 2)?,
+                            millisecond * 10u16.pow(3 - fraction.len() as u32),
+                        )
+                    }
+                }
+            }
+        };
+        if hour < 24 && minute < 60 && second < 60 {
+            Ok(Self {
+                hour,
+                minute,
+                second,
+                millisecond,
+            })
+        } else {
+            Err(ParseInputValueError)
+        }
+    }
+}
+
+impl Display for InputTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)?;
+        if self.second != 0 || self.millisecond != 0 {
+            write!(f, ":{:02}", self.second)?;
+        }
+        if self.millisecond != 0 {
+            write!(f, ".{:03}", self.millisecond)?;
+        }
+        Ok(())
+    }
+}
+
+impl super::nodes::Input {
+    /// Read the value of this input as the given type.
+    ///
+    /// ```
+    /// # use async_ui_web_html::nodes::Input;
+    /// # let _ = || {
+    /// let input = Input::new_number();
+    /// let value: Result<f64, _> = input.typed_value();
+    /// # };
+    /// ```
+    pub fn typed_value<T: InputValue>(&self) -> Result<T, InputValueError> {
+        T::read(&self.element)
+    }
+    /// Set the value of this input from the given type.
+    pub fn set_typed_value<T: InputValue>(&self, value: &T) {
+        value.write(&self.element)
+    }
+    /// Wait for the user to change the value.
+    /// Yields the new value read as the given type.
+    ///
+    /// The returned Stream never ends.
+    pub fn value_stream<T: InputValue>(
+        &self,
+    ) -> impl Stream<Item = Result<T, InputValueError>> + Unpin {
+        let element = self.element.clone();
+        AsRef::<EventTarget>::as_ref(&self.element)
+            .until_event::<web_sys::Event>(T::EVENT.into())
+            .map(move |_| T::read(&element))
+    }
+    /// Wait for the user to check or uncheck this checkbox or radio button.
+    /// Yields the new `checked` state.
+    ///
+    /// The returned Stream never ends.
+    ///
+    /// ```
+    /// # use async_ui_web_html::nodes::Input;
+    /// # use futures_lite::StreamExt;
+    /// # let _ = async {
+    /// let checkbox = Input::new_checkbox();
+    /// let mut changes = checkbox.checked_stream();
+    /// while let Some(checked) = changes.next().await {
+    ///     // ...
+    /// }
+    /// # };
+    /// ```
+    pub fn checked_stream(&self) -> impl Stream<Item = bool> + Unpin {
+        self.value_stream::<bool>().map(|checked| checked.unwrap())
+    }
+    /// Wait for the user to change the value of this `number` or `range` input.
+    /// Yields the new value, or why it isn't a valid number.
+    ///
+    /// The returned Stream never ends.
+    pub fn number_stream(&self) -> impl Stream<Item = Result<f64, InputValueError>> + Unpin {
+        self.value_stream()
+    }
+    /// Wait for the user to change the value of this `date` input.
+    /// Yields the new date, or why there isn't a valid date.
+    ///
+    /// The returned Stream never ends.
+    pub fn date_stream(&self) -> impl Stream<Item = Result<InputDate, InputValueError>> + Unpin {
+        self.value_stream()
+    }
+    /// Wait for the user to change the value of this `time` input.
+    /// Yields the new time, or why there isn't a valid time.
+    ///
+    /// The returned Stream never ends.
+    pub fn time_stream(&self) -> impl Stream<Item = Result<InputTime, InputValueError>> + Unpin {
+        self.value_stream()
+    }
+    /// Keep the value of this input and the value in the [ReactiveCell] in sync.
+    ///
+    /// The input is first set to the value in the cell. After that, whenever
+    /// the user changes the input, the cell is updated, and whenever the
+    /// cell is modified, the input is updated.
+    ///
+    /// While the input doesn't contain a valid value (for example, when the
+    /// user has cleared a `number` input), the cell keeps its last value.
+    ///
+    /// This async method never completes.
+    ///
+    /// ```
+    /// # use async_ui_web_html::nodes::Input;
+    /// # use async_ui_web_core::combinators::join;
+    /// # use async_ui_internal_utils::reactive_cell::ReactiveCell;
+    /// # let _ = async {
+    /// let celsius = ReactiveCell::new(20.0);
+    /// let input = Input::new_number();
+    /// join((
+    ///     input.render(),
+    ///     input.bind(&celsius),
+    ///     celsius.for_each(|c| {
+    ///         // show the temperature in fahrenheit
+    ///     }),
+    /// )).await;
+    /// # };
+    /// ```
+    pub async fn bind<T: InputValue + PartialEq>(&self, cell: &ReactiveCell<T>) {
+        cell.borrow().write(&self.element);
+        join((
+            async {
+                let mut changes = self.value_stream::<T>();
+                while let Some(value) = changes.next().await {
+                    if let Ok(value) = value {
+                        if *cell.borrow() != value {
+                            *cell.borrow_mut() = value;
+                        }
+                    }
+                }
+            },
+            async {
+                let mut changes = cell.until_change();
+                while changes.next().await.is_some() {
+                    let value = cell.borrow();
+                    // Don't overwrite what the user is typing if it already
+                    // means the same thing (e.g. "1.0" and 1.0).
+                    if T::read(&self.element).as_ref() != Ok(&*value) {
+                        value.write(&self.element);
+                    }
+                }
+            },
+        ))
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_date() {
+        assert_eq!(
+            "2023-12-01".parse(),
+            Ok(InputDate {
+                year: 2023,
+                month: 12,
+                day: 1
+            })
+        );
+        assert_eq!(
+            "12345-06-07".parse(),
+            Ok(InputDate {
+                year: 12345,
+                month: 6,
+                day: 7
+            })
+        );
+        for invalid in [
+            "",
+            "2023-1-01",
+            "2023-13-01",
+            "2023-00-10",
+            "2023-04-31",
+            "2023-02-29",
+            "1900-02-29",
+            "0000-01-01",
+            "023-01-01",
+            "2023-01-01-",
+            "2023/01/01",
+            "+2023-01-01",
+        ] {
+            assert_eq!(invalid.parse::<InputDate>(), Err(ParseInputValueError));
+        }
+        assert!("2000-02-29".parse::<InputDate>().is_ok());
+    }
+
+    #[test]
+    fn parse_time() {
+        let time = |hour, minute, second, millisecond| InputTime {
+            hour,
+            minute,
+            second,
+            millisecond,
+        };
+        assert_eq!("00:00".parse(), Ok(time(0, 0, 0, 0)));
+        assert_eq!("23:59:58".parse(), Ok(time(23, 59, 58, 0)));
+        assert_eq!("07:08:09.5".parse(), Ok(time(7, 8, 9, 500)));
+        assert_eq!("07:08:09.05".parse(), Ok(time(7, 8, 9, 50)));
+        assert_eq!("07:08:09.123".parse(), Ok(time(7, 8, 9, 123)));
+        for invalid in [
+            "",
+            "7:08",
+            "24:00",
+            "12:60",
+            "12:30:60",
+            "12:30:",
+            "12:30:00.",
+            "12:30:00.1234",
+            "12:30.5",
+            "12-30",
+            "12:30:0a",
+        ] {
+            assert_eq!(invalid.parse::<InputTime>(), Err(ParseInputValueError));
+        }
+    }
+
+    #[test]
+    fn format_roundtrip() {
+        for s in ["0001-01-01", "2024-02-29", "12345-12-31"] {
+            assert_eq!(s.parse::<InputDate>().unwrap().to_string(), s);
+        }
+        for s in ["00:00", "12:34:56", "12:34:00.001", "23:59:59.999"] {
+            assert_eq!(s.parse::<InputTime>().unwrap().to_string(), s);
+        }
+    }
+}
@@ -1,7 +1,12 @@
 mod common_components;
 mod common_events;
+mod event_delegation;
 mod event_handling;
+mod file_reading;
 mod input_types;
+mod input_values;
+mod media_control;
+mod observer_handling;
 mod text_node;
 
 pub mod events {
@@ -30,8 +35,11 @@ pub mod events {
     ```
     */
 
-    pub use super::common_events::{EmitElementEvent, EmitHtmlElementEvent};
-    pub use super::event_handling::{EmitEvent, EventFutureStream};
+    pub use super::common_events::{
+        EmitDocumentEvent, EmitElementEvent, EmitFormElementEvent, EmitHtmlElementEvent,
+        EmitMediaElementEvent, EmitWindowEvent,
+    };
+    pub use super::event_handling::{EmitEvent, EventFutureStream, OverflowPolicy};
 }
 pub mod nodes {
     /*!
@@ -50,5 +58,51 @@ pub mod nodes {
     [Anchor] corresponding to `<a>` and [Bold] corresponding to `<b>`.
     */
     pub use super::common_components::*;
+    pub use super::input_values::{
+        InputDate, InputTime, InputValue, InputValueError, ParseInputValueError,
+    };
     pub use super::text_node::Text;
 }
+pub mod files {
+    /*!
+    For reading files picked by the user, from a file input or a drop.
+    */
+    pub use super::file_reading::{
+        dropped_files, until_file_drop, FileContent, FileDrops, FileHandle, FileRead,
+        FileReadError, ProgressStream, ReadProgress,
+    };
+}
+pub mod media {
+    /*!
+    Async control of `<audio>` and `<video>` elements.
+    */
+    pub use super::media_control::{MediaControl, MediaPlayError};
+}
+pub mod observers {
+    /*!
+    For observing changes to elements' size, visibility, and content.
+
+    ```
+    # use async_ui_web_html::nodes::{Div, Text};
+    # use async_ui_web_core::combinators::join;
+    # let _ = async {
+    use async_ui_web_html::observers::until_resize;
+    let div = Div::new();
+    let text = Text::new();
+    join((
+        div.render(text.render()),
+        async {
+            let mut sizes = until_resize(&div);
+            loop {
+                let rect = (&mut sizes).await; // 👈 wait for resize!!!
+                text.set_data(&format!("{} x {}", rect.width(), rect.height()));
+            }
+        }
+    )).await;
+    # };
+    ```
+    */
+    pub use super::observer_handling::{
+        until_intersection, until_mutation, until_resize, ObserverFutureStream,
+    };
+}
@@ -0,0 +1,155 @@
+use std::{fmt::Display, future::Future, time::Duration};
+
+use futures_core::Stream;
+use futures_lite::StreamExt;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlMediaElement;
+
+use crate::events::EmitMediaElementEvent;
+
+/// Why [play_async][MediaControl::play_async] failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MediaPlayError {
+    /// The browser doesn't allow playing without user interaction (autoplay policy),
+    /// or the user denied it.
+    NotAllowed,
+    /// The media source isn't supported or couldn't be loaded.
+    NotSupported,
+    /// Playback was interrupted by a call to `pause()` or a change of source
+    /// before it could start.
+    Aborted,
+    /// Any other error, with its message.
+    Other(String),
+}
+
+impl Display for MediaPlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAllowed => f.write_str("playback is not allowed"),
+            Self::NotSupported => f.write_str("the media is not supported"),
+            Self::Aborted => f.write_str("playback was aborted"),
+            Self::Other(message) => write!(f, "playback failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for MediaPlayError {}
+
+impl From<JsValue> for MediaPlayError {
+    fn from(value: JsValue) -> Self {
+        // Promise rejections are DOMExceptions; tell them apart by name
+        let get = |key: &str| {
+            js_sys::Reflect::get(&value, &key.into())
+                .ok()
+                .and_then(|v| v.as_string())
+        };
+        match get("name").as_deref() {
+            Some("NotAllowedError") => Self::NotAllowed,
+            Some("NotSupportedError") => Self::NotSupported,
+            Some("AbortError") => Self::Aborted,
+            _ => Self::Other(get("message").unwrap_or_else(|| format!("{value:?}"))),
+        }
+    }
+}
+
+/// Convert a time in seconds, as used by the media API, into a [Duration].
+/// Returns `None` for unknown (NaN) or unbounded (infinite) times.
+pub(crate) fn seconds_to_duration(seconds: f64) -> Option<Duration> {
+    seconds
+        .is_finite()
+        .then(|| Duration::from_secs_f64(seconds.max(0.0)))
+}
+
+/// Async control of `<audio>` and `<video>` elements, such as
+/// [Audio][crate::nodes::Audio] and [Video][crate::nodes::Video].
+///
+/// For events such as `ended` or `canplay`, see [EmitMediaElementEvent].
+///
+/// ```
+/// # use async_ui_web_html::{media::MediaControl, events::EmitMediaElementEvent, nodes::Video};
+/// # use std::time::Duration;
+/// # let _ = async {
+/// let video = Video::new();
+/// video.set_src("intro.mp4");
+/// video.seek(Duration::from_secs(30)).await;
+/// if video.play_async().await.is_ok() {
+///     video.until_ended().await;
+/// }
+/// # };
+/// ```
+pub trait MediaControl: AsRef<HtmlMediaElement> {
+    /// Start playing. The returned Future completes when playback has started,
+    /// or fails if it can't (for example because of the browser's autoplay policy).
+    ///
+    /// Playback is requested immediately, not when the Future is first polled.
+    fn play_async(&self) -> impl Future<Output = Result<(), MediaPlayError>> + 'static {
+        let promise = self.as_ref().play();
+        async move {
+            JsFuture::from(promise?).await?;
+            Ok(())
+        }
+    }
+    /// The current playback position.
+    fn position(&self) -> Duration {
+        seconds_to_duration(self.as_ref().current_time()).unwrap_or_default()
+    }
+    /// The length of the media, if known.
+    /// `None` before the metadata has loaded, and for unbounded streams.
+    fn media_duration(&self) -> Option<Duration> {
+        seconds_to_duration(self.as_ref().duration())
+    }
+    /// Jump to the given position. The returned Future completes when the
+    /// `seeked` event fires, meaning the new position is ready to be shown.
+    ///
+    /// The position is set immediately, not when the Future is first polled.
+    /// If no media is loaded yet, the position is used as the starting point
+    /// when it loads, and the Future completes right away.
+    fn seek(&self, position: Duration) -> impl Future<Output = ()> + '_ {
+        let element = self.as_ref();
+        // listen before setting the time, so the event can't be missed
+        let seeked = element.until_seeked().listen_now();
+        let loaded = element.ready_state() != HtmlMediaElement::HAVE_NOTHING;
+        element.set_current_time(position.as_secs_f64());
+        async move {
+            if loaded {
+                seeked.await;
+            }
+        }
+    }
+    /// Wait until there is enough data to start playing.
+    /// Completes right away if there already is.
+    fn until_ready_to_play(&self) -> impl Future<Output = ()> + '_ {
+        let element = self.as_ref();
+        // listen now, in case the Future is only awaited after `canplay` fires
+        let canplay = element.until_canplay().listen_now();
+        async move {
+            if element.ready_state() < HtmlMediaElement::HAVE_FUTURE_DATA {
+                canplay.await;
+            }
+        }
+    }
+    /// The playback position, each time the browser reports it has changed
+    /// (the `timeupdate` event, typically 4 to 60 times per second while playing).
+    fn position_stream(&self) -> impl Stream<Item = Duration> + Unpin + '_ {
+        let element = self.as_ref();
+        element
+            .until_timeupdate()
+            .map(move |_| seconds_to_duration(element.current_time()).unwrap_or_default())
+    }
+}
+
+impl MediaControl for HtmlMediaElement {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_seconds() {
+        assert_eq!(seconds_to_duration(1.5), Some(Duration::from_millis(1500)));
+        assert_eq!(seconds_to_duration(-0.0), Some(Duration::ZERO));
+        assert_eq!(seconds_to_duration(f64::NAN), None);
+        assert_eq!(seconds_to_duration(f64::INFINITY), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    rc::Rc,
+    task::{Poll, Waker},
+};
+
+use async_ui_internal_utils::dummy_waker::dummy_waker;
+use futures_core::Stream;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+use web_sys::{
+    DomRectReadOnly, Element, IntersectionObserver, IntersectionObserverEntry,
+    IntersectionObserverInit, MutationObserver, MutationObserverInit, MutationRecord, Node,
+    ResizeObserver, ResizeObserverEntry,
+};
+
+/// A struct implementing both [Future] and [Stream].
+/// Yields what an observer (`ResizeObserver`, `IntersectionObserver`,
+/// or `MutationObserver`) reports.
+///
+/// Use [until_resize], [until_intersection], or [until_mutation] to get this struct.
+///
+/// The observer starts observing when this is first polled,
+/// and is disconnected when this is dropped.
+///
+/// ### Notes for the Stream API
+///
+/// *   The returned Stream is never exhausted.
+/// *   Streams from [until_resize] only keep the latest size.
+///     Streams from [until_intersection] and [until_mutation] keep every
+///     entry/record until it is yielded.
+pub struct ObserverFutureStream<T> {
+    state: State,
+    shared: Rc<RefCell<Shared<T>>>,
+    convert: fn(JsValue) -> T,
+}
+
+enum State {
+    /// Not observing yet; we start on first poll.
+    Unregistered(Box<dyn FnOnce(&js_sys::Function) -> Observer>),
+    Registered {
+        _observer: Observer,
+        _closure: Closure<dyn Fn(js_sys::Array)>,
+    },
+    /// Only while we are switching from unregistered to registered.
+    Empty,
+}
+
+enum Observer {
+    Resize(ResizeObserver),
+    Intersection(IntersectionObserver),
+    Mutation(MutationObserver),
+}
+
+struct Shared<T> {
+    queue: VecDeque<T>,
+    latest_only: bool,
+    waker: Waker,
+}
+
+impl<T: 'static> ObserverFutureStream<T> {
+    fn new(
+        start: Box<dyn FnOnce(&js_sys::Function) -> Observer>,
+        convert: fn(JsValue) -> T,
+        latest_only: bool,
+    ) -> Self {
+        Self {
+            state: State::Unregistered(start),
+            shared: Rc::new(RefCell::new(Shared {
+                queue: VecDeque::new(),
+                latest_only,
+                waker: dummy_waker(),
+            })),
+            convert,
+        }
+    }
+}
+
+/// Wait until the element's size changes.
+/// Yields the new content rect of the element.
+///
+/// The browser always reports the initial size right after observing starts,
+/// so the first item arrives soon after the first poll.
+///
+/// ```
+/// # use async_ui_web_html::{nodes::Div, observers::until_resize};
+/// # use futures_lite::StreamExt;
+/// # let _ = async {
+/// # let div = Div::new();
+/// let mut sizes = until_resize(&div);
+/// while let Some(rect) = sizes.next().await {
+///     let (width, height) = (rect.width(), rect.height());
+/// }
+/// # };
+/// ```
+///
+/// See [MDN documentation for `ResizeObserver`](https://developer.mozilla.org/en-US/docs/Web/API/ResizeObserver).
+#[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+pub fn until_resize(element: &Element) -> ObserverFutureStream<DomRectReadOnly> {
+    let element = element.to_owned();
+    ObserverFutureStream::new(
+        Box::new(move |callback| {
+            let observer = ResizeObserver::new(callback).unwrap_throw();
+            observer.observe(&element);
+            Observer::Resize(observer)
+        }),
+        |entry| entry.unchecked_into::<ResizeObserverEntry>().content_rect(),
+        true,
+    )
+}
+
+/// Wait until the element's intersection with its root (by default, the viewport)
+/// crosses one of the thresholds in `options`.
+/// Yields the entries the observer reports.
+///
+/// ```
+/// # use async_ui_web_html::{nodes::Div, observers::until_intersection};
+/// # let _ = async {
+/// # let div = Div::new();
+/// let options = web_sys::IntersectionObserverInit::new();
+/// options.set_threshold(&0.5.into());
+/// let mut entries = until_intersection(&div, &options);
+/// loop {
+///     let entry = (&mut entries).await;
+///     if entry.is_intersecting() {
+///         // at least half visible
+///     }
+/// }
+/// # };
+/// ```
+///
+/// See [MDN documentation for `IntersectionObserver`](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver).
+#[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+pub fn until_intersection(
+    element: &Element,
+    options: &IntersectionObserverInit,
+) -> ObserverFutureStream<IntersectionObserverEntry> {
+    let element = element.to_owned();
+    let options = options.to_owned();
+    ObserverFutureStream::new(
+        Box::new(move |callback| {
+            let observer =
+                IntersectionObserver::new_with_options(callback, &options).unwrap_throw();
+            observer.observe(&element);
+            Observer::Intersection(observer)
+        }),
+        JsCast::unchecked_into,
+        false,
+    )
+}
+
+/// Wait until the node (or, depending on `options`, its descendants) is mutated.
+/// Yields the records the observer reports.
+///
+/// ```
+/// # use async_ui_web_html::{nodes::Div, observers::until_mutation};
+/// # let _ = async {
+/// # let div = Div::new();
+/// let options = web_sys::MutationObserverInit::new();
+/// options.set_child_list(true);
+/// options.set_subtree(true);
+/// let record = until_mutation(&div, &options).await;
+/// # };
+/// ```
+///
+/// **Panics** if `options` is invalid (for example if none of `childList`,
+/// `attributes`, and `characterData` is set).
+///
+/// See [MDN documentation for `MutationObserver`](https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver).
+#[must_use = "the returned object is a Future+Stream that does nothing unless polled"]
+pub fn until_mutation(
+    node: &Node,
+    options: &MutationObserverInit,
+) -> ObserverFutureStream<MutationRecord> {
+    let node = node.to_owned();
+    let options = options.to_owned();
+    ObserverFutureStream::new(
+        Box::new(move |callback| {
+            let observer = MutationObserver::new(callback).unwrap_throw();
+            observer
+                .observe_with_options(&node, &options)
+                .expect_throw("invalid MutationObserverInit");
+            Observer::Mutation(observer)
+        }),
+        JsCast::unchecked_into,
+        false,
+    )
+}
+
+impl<T: 'static> Future for ObserverFutureStream<T> {
+    type Output = T;
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(Some(item)) => Poll::Ready(item),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<T: 'static> Stream for ObserverFutureStream<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        {
+            let shared = &mut *this.shared.borrow_mut();
+            let waker = cx.waker();
+            if !shared.waker.will_wake(waker) {
+                shared.waker = waker.to_owned();
+            }
+        }
+
+        if let State::Unregistered(_) = &this.state {
+            let State::Unregistered(start) = std::mem::replace(&mut this.state, State::Empty)
+            else {
+                unreachable!()
+            };
+            let shared_weak = Rc::downgrade(&this.shared);
+            let convert = this.convert;
+            let closure = Closure::new(move |entries: js_sys::Array| {
+                if let Some(strong) = shared_weak.upgrade() {
+                    let shared = &mut *strong.borrow_mut();
+                    if shared.latest_only {
+                        shared.queue.clear();
+                        shared.queue.extend(entries.iter().last().map(convert));
+                    } else {
+                        shared.queue.extend(entries.iter().map(convert));
+                    }
+                    shared.waker.wake_by_ref();
+                }
+                async_ui_web_core::executor::run_now();
+            });
+            let observer = start(closure.as_ref().unchecked_ref());
+            this.state = State::Registered {
+                _observer: observer,
+                _closure: closure,
+            };
+            Poll::Pending
+        } else if let Some(item) = this.shared.borrow_mut().queue.pop_front() {
+            Poll::Ready(Some(item))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        match self {
+            Observer::Resize(observer) => observer.disconnect(),
+            Observer::Intersection(observer) => observer.disconnect(),
+            Observer::Mutation(observer) => observer.disconnect(),
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use std::{
+    fmt::Display,
     future::{pending, Pending},
     ops::Deref,
 };
 
+use async_ui_internal_utils::reactive_cell::ReactiveCell;
 use async_ui_web_core::{window::DOCUMENT, ContainerNodeFuture};
 
 /// An HTML text node.
@@ -19,6 +21,25 @@ impl Text {
     pub fn render(&self) -> ContainerNodeFuture<Pending<()>> {
         ContainerNodeFuture::new(pending(), self.node.clone().into())
     }
+    /// Keep the content of this text node the same as the
+    /// [Display] output of the value in the [ReactiveCell].
+    ///
+    /// This async method never completes.
+    ///
+    /// ```
+    /// # use async_ui_web_html::nodes::Text;
+    /// # use async_ui_web_core::combinators::join;
+    /// # use async_ui_internal_utils::reactive_cell::ReactiveCell;
+    /// # let _ = async {
+    /// let count = ReactiveCell::new(0);
+    /// let text = Text::new();
+    /// join((text.render(), text.bind_display(&count))).await;
+    /// # };
+    /// ```
+    pub async fn bind_display<T: Display>(&self, cell: &ReactiveCell<T>) {
+        cell.for_each(|value| self.node.set_data(&value.to_string()))
+            .await
+    }
 }
 
 impl Default for Text {
@@ -1,5 +1,7 @@
 mod css;
 use css::css_macro;
+mod routes;
+use routes::routes_macro;
 mod select;
 use select::select_macro;
 
@@ -15,3 +17,10 @@ pub fn css(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn select(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     select_macro(input)
 }
+
+/// Derive `Routes` for an enum, so it can be used with `async_ui_web`'s `Router`.
+/// See the documentation of the `async_ui_web::router` module.
+#[proc_macro_derive(Routes, attributes(route))]
+pub fn routes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    routes_macro(input)
+}
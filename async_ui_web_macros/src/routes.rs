@@ -0,0 +1,207 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+pub fn routes_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match generate(input) {
+        Ok(r) => r,
+        Err(e) => e.to_compile_error(),
+    }
+    .into()
+}
+
+enum Segment {
+    Literal(String),
+    /// Index into the variant's fields.
+    Param(usize),
+}
+
+struct Template {
+    segments: Vec<Segment>,
+    /// Ends with `/*`: matches any path that starts with the segments.
+    prefix: bool,
+}
+
+/// Parse `"/users/:id/*"` against the names of the variant's fields
+/// (`None` for tuple fields, which are matched in order).
+fn parse_template(lit: &LitStr, fields: &[Option<String>]) -> syn::Result<Template> {
+    let value = lit.value();
+    let error = |message: String| syn::Error::new(lit.span(), message);
+    let Some(path) = value.strip_prefix('/') else {
+        return Err(error("route paths must start with `/`".into()));
+    };
+    let mut segments = Vec::new();
+    let mut prefix = false;
+    let mut used = vec![false; fields.len()];
+    let mut next_positional = 0;
+    let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    for (i, part) in parts.iter().enumerate() {
+        if *part == "*" {
+            if i != parts.len() - 1 {
+                return Err(error("`*` may only be the last segment".into()));
+            }
+            prefix = true;
+        } else if let Some(name) = part.strip_prefix(':') {
+            let index = if name.is_empty() {
+                return Err(error("parameters need a name, as in `:id`".into()));
+            } else if fields.iter().all(Option::is_none) {
+                next_positional += 1;
+                next_positional - 1
+            } else {
+                fields
+                    .iter()
+                    .position(|f| f.as_deref() == Some(name))
+                    .ok_or_else(|| error(format!("no field named `{name}` in this variant")))?
+            };
+            match used.get_mut(index) {
+                Some(false) => used[index] = true,
+                Some(true) => return Err(error(format!("parameter `{name}` appears twice"))),
+                None => return Err(error("more parameters than fields".into())),
+            }
+            segments.push(Segment::Param(index));
+        } else {
+            segments.push(Segment::Literal((*part).to_owned()));
+        }
+    }
+    if used.iter().any(|u| !u) {
+        return Err(error(
+            "every field of the variant must be a parameter of the path".into(),
+        ));
+    }
+    Ok(Template { segments, prefix })
+}
+
+fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`Routes` can only be derived for enums",
+        ));
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let private = quote!(::async_ui_web::__private_macro_only::router);
+    let mut matchers = Vec::new();
+    let mut writers = Vec::new();
+    for (variant_index, variant) in data.variants.iter().enumerate() {
+        let mut templates = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("route"))
+            .map(|attr| attr.parse_args::<LitStr>());
+        let lit = match (templates.next(), templates.next()) {
+            (Some(lit), None) => lit?,
+            (None, _) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "missing `#[route(\"/path\")]` attribute",
+                ))
+            }
+            (Some(_), Some(_)) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "only one `#[route]` attribute is allowed per variant",
+                ))
+            }
+        };
+        let field_names: Vec<Option<String>> = variant
+            .fields
+            .iter()
+            .map(|f| f.ident.as_ref().map(|i| i.to_string()))
+            .collect();
+        let template = parse_template(&lit, &field_names)?;
+        let variant_ident = &variant.ident;
+        let label = syn::Lifetime::new(
+            &format!("'__route_{variant_index}"),
+            proc_macro2::Span::mixed_site(),
+        );
+        let bindings: Vec<_> = (0..variant.fields.len())
+            .map(|i| format_ident!("__param_{}", i))
+            .collect();
+        let construct = match &variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|f| &f.ident);
+                quote!(Self::#variant_ident { #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!(Self::#variant_ident ( #(#bindings),* )),
+            Fields::Unit => quote!(Self::#variant_ident),
+        };
+
+        let len = template.segments.len();
+        let length_check = if template.prefix {
+            quote!(segments.len() < #len)
+        } else {
+            quote!(segments.len() != #len)
+        };
+        let segment_checks = template.segments.iter().enumerate().map(|(i, seg)| match seg {
+            Segment::Literal(lit) => quote!(
+                if segments[#i] != #lit {
+                    break #label;
+                }
+            ),
+            Segment::Param(field) => {
+                let binding = &bindings[*field];
+                quote!(
+                    let ::core::option::Option::Some(#binding) = #private::parse_param(segments[#i]) else {
+                        break #label;
+                    };
+                )
+            }
+        });
+        matchers.push(quote!(
+            #label: {
+                if #length_check {
+                    break #label;
+                }
+                #(#segment_checks)*
+                return ::core::option::Option::Some((#construct, #len));
+            }
+        ));
+
+        let pattern = match &variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|f| &f.ident);
+                quote!(Self::#variant_ident { #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!(Self::#variant_ident ( #(#bindings),* )),
+            Fields::Unit => quote!(Self::#variant_ident),
+        };
+        let pushes = template.segments.iter().map(|seg| match seg {
+            Segment::Literal(lit) => quote!(
+                path.push('/');
+                path.push_str(#lit);
+            ),
+            Segment::Param(field) => {
+                let binding = &bindings[*field];
+                quote!(
+                    path.push('/');
+                    #private::write_param(&mut path, #binding);
+                )
+            }
+        });
+        writers.push(quote!(
+            #pattern => {
+                #(#pushes)*
+            }
+        ));
+    }
+    Ok(quote!(
+        impl #impl_generics ::async_ui_web::router::Routes for #name #ty_generics #where_clause {
+            fn match_segments(segments: &[&str]) -> ::core::option::Option<(Self, usize)> {
+                #(#matchers)*
+                ::core::option::Option::None
+            }
+            fn to_path(&self) -> ::std::string::String {
+                let mut path = ::std::string::String::new();
+                match self {
+                    #(#writers)*
+                }
+                if path.is_empty() {
+                    path.push('/');
+                }
+                path
+            }
+        }
+    ))
+}